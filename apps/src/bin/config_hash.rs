@@ -0,0 +1,37 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use strategies::{config_hash, RiscVotingProtocolConfig};
+
+/// Prints the `votingProtocolConfigHash` the plugin stores for a config.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the JSON config
+    config: PathBuf,
+
+    /// Print the canonical form of the config the plugin must store instead of its hash
+    #[clap(long)]
+    canonical: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    // The guests hash the canonical form of the config they are given, so the plugin must store
+    // that form rather than the file as written.
+    let config = fs::read_to_string(&args.config)
+        .with_context(|| format!("failed to read {}", args.config.display()))?;
+    let config = serde_json::from_str::<RiscVotingProtocolConfig>(&config)
+        .context("invalid config")?
+        .to_json();
+    if args.canonical {
+        // No trailing newline, so scripts can store the output as is.
+        print!("{}", config);
+    } else {
+        println!("{}", config_hash(&config));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use alloy::{hex, sol_types::SolValue};
+use anyhow::{Context, Result};
+use apps::describe_commitment;
+use clap::{Parser, ValueEnum};
+use journal::{ExecutionJournal, ProposalJournal, VotingJournal};
+
+#[derive(Clone, Debug, ValueEnum)]
+enum JournalKind {
+    Voting,
+    Execution,
+    Proposal,
+}
+
+/// Decodes a journal committed by one of the guests, e.g. from the calldata of a `vote`,
+/// `execute` or `createProposalWithProof` transaction.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Guest that committed the journal
+    #[clap(long, value_enum)]
+    kind: JournalKind,
+
+    /// ABI encoded journal, as hex
+    journal: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let journal = hex::decode(&args.journal).context("invalid hex")?;
+    match args.kind {
+        JournalKind::Voting => {
+            let journal = VotingJournal::abi_decode(&journal, true).context("invalid journal")?;
+            println!(
+                "Steel commitment: {}",
+                describe_commitment(&journal.commitment)?
+            );
+            println!("Config contract: {}", journal.configContract);
+            println!("Config hash: {}", journal.configHash);
+            println!("Proposal ID: {}", journal.proposalId);
+            println!("Voter: {}", journal.voter);
+            println!("Balance: {}", journal.balance);
+            println!("Direction: {}", journal.direction);
+            if !journal.amounts.is_empty() {
+                println!("Amounts: {:?}", journal.amounts);
+            }
+            if !journal.choices.is_empty() {
+                println!("Choices: {:?}", journal.choices);
+            }
+            for commitment in &journal.lookbackCommitments {
+                println!("Look-back commitment: {}", describe_commitment(commitment)?);
+            }
+            for chain_commitment in &journal.chainCommitments {
+                println!(
+                    "Chain {} commitment: {}",
+                    chain_commitment.chainId,
+                    describe_commitment(&chain_commitment.commitment)?
+                );
+            }
+        }
+        JournalKind::Execution => {
+            let journal =
+                ExecutionJournal::abi_decode(&journal, true).context("invalid journal")?;
+            println!(
+                "Steel commitment: {}",
+                describe_commitment(&journal.commitment)?
+            );
+            println!("Config contract: {}", journal.configContract);
+            println!("Config hash: {}", journal.configHash);
+            println!("Proposal ID: {}", journal.proposalId);
+            println!("Option count: {}", journal.optionCount);
+            println!("Tally: {:?}", journal.tally);
+            println!("Winning option: {}", journal.winner);
+            for chain_commitment in &journal.chainCommitments {
+                println!(
+                    "Chain {} commitment: {}",
+                    chain_commitment.chainId,
+                    describe_commitment(&chain_commitment.commitment)?
+                );
+            }
+        }
+        JournalKind::Proposal => {
+            let journal = ProposalJournal::abi_decode(&journal, true).context("invalid journal")?;
+            println!(
+                "Steel commitment: {}",
+                describe_commitment(&journal.commitment)?
+            );
+            println!("Config contract: {}", journal.configContract);
+            println!("Config hash: {}", journal.configHash);
+            println!("Proposer: {}", journal.proposer);
+            println!("Voting power: {}", journal.votingPower);
+            for commitment in &journal.lookbackCommitments {
+                println!("Look-back commitment: {}", describe_commitment(commitment)?);
+            }
+            for chain_commitment in &journal.chainCommitments {
+                println!(
+                    "Chain {} commitment: {}",
+                    chain_commitment.chainId,
+                    describe_commitment(&chain_commitment.commitment)?
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use alloy::{
+    network::EthereumWallet, signers::local::PrivateKeySigner, transports::http::reqwest::Url,
+};
+use alloy_primitives::{Address, Bytes, U256};
+use anyhow::{Context, Result};
+use apps::{
+    describe_commitment, parse_chain_rpc_url, proposal::ProposalRequest, IProposals::Action,
+    ProposalProver,
+};
+use clap::Parser;
+use strategies::voting_strategies::CensusProof;
+use tracing_subscriber::EnvFilter;
+
+/// Arguments of the proposal publisher CLI.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Ethereum chain ID
+    #[clap(long)]
+    chain_id: u64,
+
+    /// Private key of the proposer, which sends the proposal.
+    #[clap(long, env)]
+    eth_wallet_private_key: PrivateKeySigner,
+
+    /// Ethereum Node endpoint.
+    #[clap(long, env)]
+    rpc_url: String,
+
+    /// Ethereum block number. If not provided, the latest block is used.
+    #[clap(long)]
+    block_number: Option<u64>,
+
+    /// Beacon API endpoint. When set, the proof commits to the block's beacon block root
+    /// (EIP-4788) instead of its block hash.
+    #[clap(long, env)]
+    beacon_api_url: Option<String>,
+
+    /// RPC endpoint of another chain holding assets, as `<CHAIN_ID>=<RPC_URL>`. Can be repeated.
+    #[clap(long, value_parser = parse_chain_rpc_url)]
+    chain_rpc_url: Vec<(u64, Url)>,
+
+    /// Plugin's contract address on Ethereum
+    #[clap(long)]
+    config_contract: Address,
+
+    /// Additional delegation data
+    #[clap(long)]
+    additional_delegation_data: String,
+
+    /// Merkle census leaves and proofs as a JSON array
+    #[clap(long, default_value = "[]")]
+    census_proofs: String,
+
+    /// Metadata of the proposal
+    #[clap(long, default_value = "0x")]
+    metadata: Bytes,
+
+    /// Actions of the proposal as a JSON array of `{"to", "value", "data"}` objects
+    #[clap(long, default_value = "[]")]
+    actions: String,
+
+    /// Bitmap of the actions allowed to fail
+    #[clap(long, default_value = "0")]
+    allow_failure_map: U256,
+
+    /// Start date of the proposal vote, 0 to start immediately
+    #[clap(long, default_value = "0")]
+    start_date: u64,
+
+    /// End date of the proposal vote, 0 to use the minimum duration
+    #[clap(long, default_value = "0")]
+    end_date: u64,
+
+    // If proving should be disabled
+    #[clap(long)]
+    testing: u8,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing. In order to view logs, run `RUST_LOG=info cargo run`
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    // parse the command line arguments
+    let args = Args::parse();
+
+    let mut builder = ProposalProver::builder(Url::from_str(&args.rpc_url)?)
+        .wallet(EthereumWallet::from(args.eth_wallet_private_key.clone()));
+    if let Some(beacon_api_url) = &args.beacon_api_url {
+        builder = builder.beacon_api_url(Url::from_str(beacon_api_url)?);
+    }
+    for (chain_id, rpc_url) in &args.chain_rpc_url {
+        builder = builder.chain_rpc_url(*chain_id, rpc_url.clone());
+    }
+    let prover = builder.build_proposal_prover();
+
+    // The proposer is the account sending the proposal, which the plugin checks.
+    let request = ProposalRequest {
+        block_number: args.block_number,
+        proposer: args.eth_wallet_private_key.address(),
+        config_contract: args.config_contract,
+        additional_delegation_data: args.additional_delegation_data.clone(),
+        census_proofs: serde_json::from_str::<Vec<CensusProof>>(&args.census_proofs)
+            .context("invalid census proofs")?,
+        metadata: args.metadata.clone(),
+        actions: serde_json::from_str::<Vec<Action>>(&args.actions).context("invalid actions")?,
+        allow_failure_map: args.allow_failure_map,
+        start_date: args.start_date,
+        end_date: args.end_date,
+    };
+
+    let preflight = prover.preflight(&request).await?;
+    println!("Block: {}", preflight.block_number);
+    println!("Proposer voting power: {}", preflight.total_voting_power);
+    println!("proving...");
+
+    if args.testing == 1 {
+        return Ok(());
+    }
+
+    let proof = prover.prove(preflight, request.clone()).await?;
+    println!("proving...done");
+
+    println!(
+        "Steel commitment: {}",
+        describe_commitment(&proof.journal.commitment)?
+    );
+    println!("Config hash: {}", proof.journal.configHash);
+    println!("Parameters hash: {}", proof.journal.paramsHash);
+    println!("Voting power: {}", proof.journal.votingPower);
+    for chain_commitment in &proof.journal.chainCommitments {
+        println!(
+            "Chain {} commitment: {}",
+            chain_commitment.chainId,
+            describe_commitment(&chain_commitment.commitment)?
+        );
+    }
+
+    let submission = prover.submit(&proof, &request).await?;
+    println!("sending tx...done: {}", submission.tx_hash);
+
+    Ok(())
+}
@@ -1,21 +1,13 @@
 use std::str::FromStr;
 
 use alloy::{
-    network::EthereumWallet, providers::ProviderBuilder, signers::local::PrivateKeySigner,
-    sol_types::SolValue, transports::http::reqwest::Url,
+    network::EthereumWallet, signers::local::PrivateKeySigner, transports::http::reqwest::Url,
 };
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, U256};
 use anyhow::{ensure, Context, Result};
-use apps::HostContext;
-use aragon_zk_voting_protocol_methods::VOTING_PROTOCOL_ELF;
+use apps::{describe_commitment, parse_chain_rpc_url, vote::VoteRequest, VoteProver};
 use clap::Parser;
-use risc0_ethereum_contracts::encode_seal;
-use risc0_steel::{
-    ethereum::{EthEvmEnv, ETH_SEPOLIA_CHAIN_SPEC},
-    Commitment, Contract,
-};
-use risc0_zkvm::{default_prover, ExecutorEnv, ProveInfo, ProverOpts, VerifierContext};
-use tokio::task;
+use strategies::voting_strategies::CensusProof;
 use tracing_subscriber::EnvFilter;
 
 alloy::sol! {
@@ -24,24 +16,8 @@ alloy::sol! {
     interface IERC20 {
         function balanceOf(address account) external view returns (uint);
     }
-    interface ConfigContract {
-        function votingProtocolConfig(uint256 proposal_id) external view returns (string memory);
-    }
-    struct VotingJournal {
-        Commitment commitment;
-        address configContract;
-        uint256 proposalId;
-        address voter;
-        uint256 balance;
-        uint8 direction;
-    }
 }
 
-alloy::sol!(
-    #[sol(rpc, all_derives)]
-    "../contracts/IMajorityVoting.sol"
-);
-
 /// Arguments of the publisher CLI.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -58,13 +34,22 @@ struct Args {
     #[clap(long, env)]
     rpc_url: String,
 
-    /// Ethereum block number.
+    /// Snapshot block of the proposal. Read from the plugin when omitted.
     #[clap(long)]
     block_number: Option<u64>,
 
-    /// Voter's signature
+    /// Beacon API endpoint. When set, the proof commits to the snapshot's beacon block root
+    /// (EIP-4788) instead of its block hash.
+    #[clap(long, env)]
+    beacon_api_url: Option<String>,
+
+    /// RPC endpoint of another chain holding assets, as `<CHAIN_ID>=<RPC_URL>`. Can be repeated.
+    #[clap(long, value_parser = parse_chain_rpc_url)]
+    chain_rpc_url: Vec<(u64, Url)>,
+
+    /// Voter's signature of the quoted message. Required unless `--quote` is given.
     #[clap(long)]
-    voter_signature: String,
+    voter_signature: Option<String>,
 
     /// Account address to read the balance_of on Ethereum
     #[clap(long)]
@@ -78,13 +63,22 @@ struct Args {
     #[clap(long)]
     proposal_id: U256,
 
-    /// Vote direction
+    /// Vote direction, or 0 for a split vote
     #[clap(long)]
     direction: u8,
 
-    /// Voter's balance
+    /// Voting power given to abstain, yes and no by a split vote, comma separated
+    #[clap(long, value_delimiter = ',')]
+    amounts: Vec<U256>,
+
+    /// Options chosen by a ballot of a multi-option proposal, most preferred first, comma
+    /// separated
+    #[clap(long, value_delimiter = ',')]
+    choices: Vec<u8>,
+
+    /// Voter's balance. Computed by the preflight when omitted, and checked against it otherwise.
     #[clap(long)]
-    balance: U256,
+    balance: Option<U256>,
 
     /// Plugin's contract address on Ethereum
     #[clap(long)]
@@ -98,6 +92,14 @@ struct Args {
     #[clap(long)]
     additional_delegation_data: String,
 
+    /// Merkle census leaves and proofs as a JSON array
+    #[clap(long, default_value = "[]")]
+    census_proofs: String,
+
+    /// Only print the voting power and the message to sign, as JSON
+    #[clap(long)]
+    quote: bool,
+
     // If proving should be disabled
     #[clap(long)]
     testing: u8,
@@ -118,137 +120,77 @@ async fn main() -> Result<()> {
     // parse the command line arguments
     let args = Args::parse();
 
-    // Create an alloy provider for that private key and URL.
-    let wallet = EthereumWallet::from(args.eth_wallet_private_key);
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_http(Url::from_str(&args.rpc_url).unwrap());
-
-    // Create an EVM environment from an RPC endpoint and a block number. If no block number is
-    // provided, the latest block is used.
-    // Define the type for H
-    let mut env = EthEvmEnv::builder()
-        .rpc(Url::from_str(&args.rpc_url).unwrap())
-        // .beacon_api(Url::from_str(&args.rpc_url).unwrap())
-        // .provider(provider.clone())
-        .block_number(args.block_number.unwrap())
-        .build()
-        .await?;
-
-    //  The `with_chain_spec` method is used to specify the chain configuration.
-    env = env.with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
-
-    // Making the preflighs. This step is mandatory
-    let config_call = ConfigContract::votingProtocolConfigCall {
+    let mut builder = VoteProver::builder(Url::from_str(&args.rpc_url)?)
+        .wallet(EthereumWallet::from(args.eth_wallet_private_key.clone()));
+    if let Some(beacon_api_url) = &args.beacon_api_url {
+        builder = builder.beacon_api_url(Url::from_str(beacon_api_url)?);
+    }
+    for (chain_id, rpc_url) in &args.chain_rpc_url {
+        builder = builder.chain_rpc_url(*chain_id, rpc_url.clone());
+    }
+    let prover = builder.build_vote_prover();
+
+    let census_proofs = serde_json::from_str::<Vec<CensusProof>>(&args.census_proofs)
+        .context("invalid census proofs")?;
+
+    let request = VoteRequest {
+        block_number: args.block_number,
+        voter: args.voter,
+        dao: args.dao_address,
         proposal_id: args.proposal_id,
+        direction: args.direction,
+        amounts: args.amounts.clone(),
+        choices: args.choices.clone(),
+        config_contract: args.config_contract,
+        additional_delegation_data: args.additional_delegation_data.clone(),
+        census_proofs,
     };
-    let mut config_contract = Contract::preflight(args.config_contract, &mut env);
-    let config_returns = config_contract.call_builder(&config_call).call().await?;
-    println!("Config string: {:?}", config_returns._0);
-
-    let config =
-        serde_json::from_str::<apps::RiscVotingProtocolConfig>(&config_returns._0).unwrap();
-
-    let mut strategies_context = HostContext::default(&mut env);
-
-    // Get the total voting power of the voter across all assets.
-    let mut total_voting_power = U256::from(0);
-
-    for asset in &config.assets {
-        let delegations_result = strategies_context
-            .process_delegation_strategy(
-                args.voter,
-                asset,
-                Bytes::from_str(args.additional_delegation_data.as_str()).unwrap(),
-            )
-            .await;
-
-        if delegations_result.is_err() {
-            println!("Delegations given are not correct");
-            assert!(false);
-        }
-
-        let delegations = delegations_result.unwrap();
-        let mut asset_voting_power = U256::from(0);
-
-        for delegation in &delegations {
-            let strategy = asset.voting_power_strategy.clone();
-            let delegate = delegation.delegate;
-            let ratio = delegation.ratio;
-
-            // Call the async function and await the result
-            let voting_power = strategies_context
-                .process_voting_power_strategy(strategy, delegate, asset)
-                .await;
-
-            asset_voting_power += voting_power / ratio;
-        }
-        total_voting_power += asset_voting_power;
-    }
 
-    println!("Total voting power: {}", total_voting_power);
-    assert!(total_voting_power == args.balance);
+    // The preflight runs at the proposal's snapshot block and computes the voting power.
+    let preflight = prover.preflight(&request).await?;
+    let quote = &preflight.quote;
+    if let Some(balance) = args.balance {
+        ensure!(
+            balance == quote.balance,
+            "--balance {} does not match the voting power {}",
+            balance,
+            quote.balance
+        );
+    }
+    if args.quote {
+        println!("{}", serde_json::to_string(quote)?);
+        return Ok(());
+    }
+    println!("Snapshot block: {}", quote.block_number);
+    println!("Total voting power: {}", quote.balance);
+    let voter_signature = args.voter_signature.context(
+        "--voter-signature is required to prove a vote, use --quote to get the message to sign",
+    )?;
     println!("proving...");
 
     if args.testing == 1 {
         return Ok(());
     }
 
-    let view_call_input = env.into_input().await?;
-    let prove_info = task::spawn_blocking(move || -> Result<ProveInfo, anyhow::Error> {
-        let env = ExecutorEnv::builder()
-            .write(&view_call_input)?
-            .write(&args.voter_signature)?
-            .write(&args.voter)?
-            .write(&args.dao_address)?
-            .write(&args.proposal_id)?
-            .write(&args.direction)?
-            .write(&args.balance)?
-            .write(&args.config_contract)?
-            .write(&args.additional_delegation_data)?
-            .build()?;
-
-        default_prover().prove_with_ctx(
-            env,
-            &VerifierContext::default(),
-            VOTING_PROTOCOL_ELF,
-            &ProverOpts::groth16(),
-        )
-    })
-    .await?
-    .context("failed to create proof")?;
+    let proof = prover.prove(preflight, request, voter_signature).await?;
     println!("proving...done");
 
-    // Encode the groth16 seal with the selector
-    let receipt = prove_info.receipt;
-    let journal = &receipt.journal.bytes;
-
-    // Decode and log the commitment
-    let journal = VotingJournal::abi_decode(journal, true).context("invalid journal")?;
-
-    // ABI encode the seal.
-    let seal = encode_seal(&receipt).context("invalid receipt")?;
-    let seal_bytes = seal.as_slice();
-
-    // println!("journalData: {:?}", to_hex_string(journal));
-    println!("seal: {:?}", to_hex_string(seal_bytes));
-    println!("Steel commitment: {:?}", journal.commitment);
-
-    let contract = IMajorityVoting::new(args.config_contract, &provider);
-    let call_builder = contract.vote(receipt.journal.bytes.into(), seal.into());
-    println!("Send {} {}", contract.address(), call_builder.calldata());
-    log::debug!("Send {} {}", contract.address(), call_builder.calldata());
-    let pending_tx = call_builder.send().await?;
-    let tx_hash = *pending_tx.tx_hash();
-    let receipt = pending_tx
-        .get_receipt()
-        .await
-        .with_context(|| format!("transaction did not confirm: {}", tx_hash))?;
-
-    ensure!(receipt.status(), "transaction failed: {}", tx_hash);
+    println!("seal: {:?}", to_hex_string(&proof.seal));
+    println!(
+        "Steel commitment: {}",
+        describe_commitment(&proof.journal.commitment)?
+    );
+    println!("Config hash: {}", proof.journal.configHash);
+    for chain_commitment in &proof.journal.chainCommitments {
+        println!(
+            "Chain {} commitment: {}",
+            chain_commitment.chainId,
+            describe_commitment(&chain_commitment.commitment)?
+        );
+    }
 
-    println!("sending tx...done");
+    let submission = prover.submit(&proof).await?;
+    println!("sending tx...done: {}", submission.tx_hash);
 
     Ok(())
 }
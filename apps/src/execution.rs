@@ -0,0 +1,324 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Executions are preflighted at the proposal's snapshot block, proven with the execution guest
+// and sent to the plugin, which then runs the proposal's actions.
+
+use std::collections::BTreeMap;
+
+use alloy::{primitives::TxHash, sol_types::SolValue, transports::http::reqwest::Url};
+use alloy_primitives::{Address, U256};
+use anyhow::{ensure, Context, Result};
+use aragon_zk_voting_protocol_methods::guest_images;
+use journal::ExecutionJournal;
+use risc0_steel::{
+    ethereum::{EthEvmEnv, EthEvmInput},
+    Contract,
+};
+use risc0_zkvm::sha::Digest;
+use strategies::{config_hash, input::ExecutionInput};
+
+use crate::{
+    chain_envs, chain_inputs, encode_config, proposal_chain_blocks, proposal_parameters,
+    prover::{prove_input, IMajorityVoting, Proof, ProverBuilder, ProverSettings, Submission},
+    snapshot_block_number, HostContext,
+    IProposals::ProposalParameters,
+    IVotingSettings, RiscVotingProtocolConfig, RpcHostEvmEnv,
+};
+
+/// The execution of a proposal with its live tally.
+#[derive(Clone, Debug)]
+pub struct ExecutionRequest {
+    /// Must be the proposal's snapshot block when set.
+    pub block_number: Option<u64>,
+    pub dao: Address,
+    pub proposal_id: U256,
+    pub config_contract: Address,
+    /// Yes, no and abstain for majority proposals, or the recorded ballots (`getBallots`) for
+    /// multi-option proposals.
+    pub tally: Vec<U256>,
+}
+
+/// Result of the preflight: everything the execution guest needs.
+pub struct ExecutionPreflight {
+    pub block_number: u64,
+    pub total_voting_power: U256,
+    /// Number of options of the proposal, 0 for yes, no and abstain proposals.
+    pub option_count: u8,
+    /// Guest implementing the version of the proposal's config.
+    pub elf: &'static [u8],
+    evm_input: EthEvmInput,
+    chain_inputs: Vec<(u64, EthEvmInput)>,
+    config: Vec<u8>,
+}
+
+impl ExecutionPreflight {
+    /// Builds the guest input for `request`.
+    pub fn into_input(self, request: ExecutionRequest) -> ExecutionInput {
+        ExecutionInput {
+            version: ExecutionInput::VERSION,
+            evm_input: self.evm_input,
+            chain_inputs: self.chain_inputs,
+            dao: request.dao,
+            proposal_id: request.proposal_id,
+            config_contract: request.config_contract,
+            config: self.config,
+            option_count: self.option_count,
+            tally: request.tally,
+        }
+    }
+}
+
+/// Preflights, proves and submits executions. Built with [`ProverBuilder`].
+#[derive(Clone)]
+pub struct ExecutionProver {
+    settings: ProverSettings,
+}
+
+/// Proof of an execution.
+pub type ExecutionProof = Proof<ExecutionJournal>;
+
+impl ExecutionProver {
+    pub(crate) fn new(settings: ProverSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn builder(rpc_url: Url) -> ProverBuilder {
+        ProverBuilder::new(rpc_url)
+    }
+
+    /// Preflights `request` at the proposal's snapshot block. Fails if the tally does not pass
+    /// the config's execution strategy.
+    pub async fn preflight(&self, request: &ExecutionRequest) -> Result<ExecutionPreflight> {
+        // The plugin validates the commitment against the proposal's snapshot block, so the proof
+        // is made there.
+        let parameters = proposal_parameters(
+            self.settings.rpc_url.clone(),
+            request.config_contract,
+            request.proposal_id,
+        )
+        .await?;
+        let block_number = snapshot_block_number(&parameters, request.block_number)?;
+        let chain_blocks = proposal_chain_blocks(
+            self.settings.rpc_url.clone(),
+            request.config_contract,
+            request.proposal_id,
+        )
+        .await?;
+
+        let builder = EthEvmEnv::builder()
+            .rpc(self.settings.rpc_url.clone())
+            .block_number(block_number);
+
+        //  The `with_chain_spec` method is used to specify the chain configuration.
+        let (evm_input, chain_inputs, (total_voting_power, config, elf)) =
+            match &self.settings.beacon_api_url {
+                Some(beacon_api_url) => {
+                    let mut env = builder
+                        .beacon_api(beacon_api_url.clone())
+                        .build()
+                        .await?
+                        .with_chain_spec(&self.settings.chain_spec);
+                    ensure!(
+                        chain_blocks.is_empty(),
+                        "cross-chain assets need block commitments, drop the beacon API URL"
+                    );
+                    let mut chain_envs = BTreeMap::new();
+                    let preflight = preflight_execution(
+                        &mut env,
+                        &mut chain_envs,
+                        &self.settings,
+                        request,
+                        &parameters,
+                    )
+                    .await?;
+                    (env.into_input().await?, Vec::new(), preflight)
+                }
+                None => {
+                    let mut env = builder
+                        .build()
+                        .await?
+                        .with_chain_spec(&self.settings.chain_spec);
+                    let mut chain_envs = chain_envs(
+                        &self.settings.chain_rpc_urls,
+                        &chain_blocks,
+                        env.header().timestamp,
+                    )
+                    .await?;
+                    let preflight = preflight_execution(
+                        &mut env,
+                        &mut chain_envs,
+                        &self.settings,
+                        request,
+                        &parameters,
+                    )
+                    .await?;
+                    (
+                        env.into_input().await?,
+                        chain_inputs(chain_envs).await?,
+                        preflight,
+                    )
+                }
+            };
+
+        Ok(ExecutionPreflight {
+            block_number,
+            total_voting_power,
+            option_count: parameters.optionCount,
+            elf,
+            evm_input,
+            chain_inputs,
+            config,
+        })
+    }
+
+    /// Proves the execution of a preflighted request.
+    pub async fn prove(
+        &self,
+        preflight: ExecutionPreflight,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionProof> {
+        let elf = preflight.elf;
+        let input = preflight.into_input(request);
+        let (journal_bytes, seal) =
+            prove_input(elf, input, self.settings.prover_opts.clone()).await?;
+        let journal =
+            ExecutionJournal::abi_decode(&journal_bytes, true).context("invalid journal")?;
+        Ok(Proof {
+            journal,
+            journal_bytes,
+            seal,
+        })
+    }
+
+    /// Sends the execution to the plugin it was proven for, without waiting for its confirmation.
+    pub async fn send(&self, proof: &ExecutionProof) -> Result<TxHash> {
+        let provider = self.settings.wallet_provider()?;
+        let contract = IMajorityVoting::new(proof.journal.configContract, &provider);
+        let pending_tx = contract
+            .execute(proof.journal_bytes.clone(), proof.seal.clone())
+            .send()
+            .await?;
+        Ok(*pending_tx.tx_hash())
+    }
+
+    /// Waits for a transaction returned by [`Self::send`] to be confirmed.
+    pub async fn confirm(&self, tx_hash: TxHash) -> Result<Submission> {
+        self.settings.confirm(tx_hash).await
+    }
+
+    /// Sends the execution to the plugin it was proven for and waits for its confirmation.
+    pub async fn submit(&self, proof: &ExecutionProof) -> Result<Submission> {
+        let tx_hash = self.send(proof).await?;
+        self.confirm(tx_hash).await
+    }
+}
+
+/// Preflights the config, total supply and execution strategy calls on `env`, which can commit
+/// either to the snapshot block or to its beacon block root. Returns the total voting power, the
+/// binary config and the guest to prove with.
+async fn preflight_execution<C>(
+    env: &mut RpcHostEvmEnv<C>,
+    chain_envs: &mut BTreeMap<u64, RpcHostEvmEnv<C>>,
+    settings: &ProverSettings,
+    request: &ExecutionRequest,
+    parameters: &ProposalParameters,
+) -> Result<(U256, Vec<u8>, &'static [u8])>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    let config_string = &parameters.votingProtocolConfig;
+    let config = serde_json::from_str::<RiscVotingProtocolConfig>(config_string)?;
+    ensure!(
+        config_hash(config_string) == parameters.votingProtocolConfigHash,
+        "config hashes to {}, but the proposal stores {}",
+        config_hash(config_string),
+        parameters.votingProtocolConfigHash
+    );
+
+    // Proposals are proven with the guest implementing the version of their config.
+    let images = guest_images(&config.voting_protocol_version).with_context(|| {
+        format!(
+            "no guest for votingProtocolVersion {}",
+            config.voting_protocol_version
+        )
+    })?;
+    log::info!("Guest image ID: {}", Digest::from(images.execution_id));
+
+    // Making the preflighs. This step is mandatory. The proposal is created after its snapshot
+    // block, so the guest reads the plugin's config hash, which the proposal copied.
+    let config_hash_call = IVotingSettings::votingProtocolConfigHashCall {};
+    let mut config_contract = Contract::preflight(request.config_contract, env);
+    let config_hash_returns = config_contract
+        .call_builder(&config_hash_call)
+        .call()
+        .await?;
+    ensure!(
+        config_hash(config_string) == config_hash_returns._0,
+        "the plugin's config changed in the block creating the proposal, its hash was {}",
+        config_hash_returns._0
+    );
+
+    // Assets on other chains are evaluated against their own environment.
+    for asset in &config.assets {
+        ensure!(
+            asset.chain_id == settings.chain_spec.chain_id()
+                || chain_envs.contains_key(&asset.chain_id),
+            "no environment for chain {}, cross-chain assets need --chain-rpc-url and block commitments",
+            asset.chain_id
+        );
+    }
+
+    let mut strategies_context = HostContext::default(env);
+    for (chain_id, chain_env) in chain_envs.iter_mut() {
+        strategies_context.add_chain_env(*chain_id, chain_env);
+    }
+    // Multi-option proposals are executed with their own strategy.
+    let execution_strategy = config
+        .execution_strategy_for(parameters.optionCount)
+        .context("the config has no multiOptionExecutionStrategy for multi-option proposals")?
+        .to_string();
+    // The guest checks the parameters too, so a config missing them can't be proven.
+    serde_json::from_str::<strategies::RiscVotingProtocolConfig>(config_string)?
+        .check_execution_parameters(&execution_strategy)?;
+    strategies_context.configure_execution_strategies(&config);
+
+    // Get the total voting power of the voter across all assets.
+    let mut total_voting_power = U256::from(0);
+
+    for asset in &config.assets {
+        // Call the async function and await the result
+        let voting_power = strategies_context.process_total_supply(asset).await?;
+
+        total_voting_power += voting_power;
+    }
+
+    ensure!(
+        strategies_context
+            .process_execution_strategy(
+                execution_strategy.clone(),
+                total_voting_power,
+                &request.tally
+            )
+            .await?,
+        "the tally does not pass the {} execution strategy",
+        execution_strategy
+    );
+
+    Ok((
+        total_voting_power,
+        encode_config(config_string)?,
+        images.execution_elf,
+    ))
+}
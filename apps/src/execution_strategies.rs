@@ -15,10 +15,18 @@ where
         &self,
         env: &mut EthHostEvmEnv<T, N, P, H>,
         total_supply: U256,
-        tally: [U256; 3],
+        tally: &[U256],
     ) -> bool;
 }
 
+mod absolute_quorum;
+mod ballots;
 mod majority_voting;
+mod optimistic;
+mod supermajority;
 
+pub use absolute_quorum::AbsoluteQuorum;
+pub use ballots::Ballots;
 pub use majority_voting::MajorityVoting;
+pub use optimistic::Optimistic;
+pub use supermajority::Supermajority;
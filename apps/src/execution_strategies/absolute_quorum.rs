@@ -0,0 +1,40 @@
+use super::EthHostEvmEnv;
+use super::ExecutionStrategy;
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::U256;
+use async_trait::async_trait;
+
+/// Passes when the yes votes reach the config's absolute `quorum`, whatever the other votes.
+#[derive(Default)]
+pub struct AbsoluteQuorum {
+    quorum: Option<U256>,
+}
+
+impl AbsoluteQuorum {
+    pub fn new(quorum: Option<U256>) -> Self {
+        Self { quorum }
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> ExecutionStrategy<T, N, P, H> for AbsoluteQuorum
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn proof_execution(
+        &self,
+        _env: &mut EthHostEvmEnv<T, N, P, H>,
+        _total_supply: U256,
+        tally: &[U256],
+    ) -> bool {
+        match self.quorum {
+            Some(quorum) => {
+                strategies::execution_strategies::absolute_quorum_reached(tally, quorum)
+            }
+            None => false,
+        }
+    }
+}
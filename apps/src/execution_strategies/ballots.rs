@@ -0,0 +1,57 @@
+use super::EthHostEvmEnv;
+use super::ExecutionStrategy;
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::U256;
+use async_trait::async_trait;
+use strategies::{
+    execution_strategies::{approval_winner, ballots_pass, instant_runoff_winner},
+    Ratio,
+};
+
+/// Host side of the multi-option strategies, which only count the ballots. Delegates to the
+/// guest's count, so the host knows the outcome before proving.
+pub struct Ballots {
+    min_participation: Option<Ratio>,
+    winner: fn(&[U256]) -> u8,
+}
+
+impl Ballots {
+    /// The guest's `ApprovalVoting`.
+    pub fn approval_voting(min_participation: Option<Ratio>) -> Self {
+        Self {
+            min_participation,
+            winner: approval_winner,
+        }
+    }
+
+    /// The guest's `RankedChoice`.
+    pub fn ranked_choice(min_participation: Option<Ratio>) -> Self {
+        Self {
+            min_participation,
+            winner: instant_runoff_winner,
+        }
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> ExecutionStrategy<T, N, P, H> for Ballots
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn proof_execution(
+        &self,
+        _env: &mut EthHostEvmEnv<T, N, P, H>,
+        total_supply: U256,
+        tally: &[U256],
+    ) -> bool {
+        ballots_pass(
+            total_supply,
+            tally,
+            self.min_participation.as_ref(),
+            self.winner,
+        )
+    }
+}
@@ -18,8 +18,13 @@ where
         &self,
         _env: &mut EthHostEvmEnv<T, N, P, H>,
         total_supply: U256,
-        tally: [U256; 3],
+        tally: &[U256],
     ) -> bool {
+        // Majority proposals are tallied as yes, no and abstain.
+        if tally.len() != 3 {
+            return false;
+        }
+
         // TODO: The parameters for the minimum participation and so on should be flexible
         let yes_votes = tally[0];
         let no_votes = tally[1];
@@ -0,0 +1,45 @@
+use super::EthHostEvmEnv;
+use super::ExecutionStrategy;
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::U256;
+use async_trait::async_trait;
+use strategies::Ratio;
+
+/// Passes unless the no votes reach the config's `vetoThreshold` of the total supply.
+#[derive(Default)]
+pub struct Optimistic {
+    veto_threshold: Option<Ratio>,
+}
+
+impl Optimistic {
+    pub fn new(veto_threshold: Option<Ratio>) -> Self {
+        Self { veto_threshold }
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> ExecutionStrategy<T, N, P, H> for Optimistic
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn proof_execution(
+        &self,
+        _env: &mut EthHostEvmEnv<T, N, P, H>,
+        total_supply: U256,
+        tally: &[U256],
+    ) -> bool {
+        // Optimistic proposals are tallied as yes, no and abstain.
+        if tally.len() != 3 {
+            return false;
+        }
+        match &self.veto_threshold {
+            Some(veto_threshold) => {
+                !strategies::execution_strategies::vetoed(total_supply, tally[1], veto_threshold)
+            }
+            None => false,
+        }
+    }
+}
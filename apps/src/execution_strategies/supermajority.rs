@@ -0,0 +1,51 @@
+use super::EthHostEvmEnv;
+use super::ExecutionStrategy;
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::U256;
+use async_trait::async_trait;
+use strategies::Ratio;
+
+/// Passes when yes votes reach the config's `supportThreshold` of the non-abstain votes and all
+/// votes reach its `minParticipation` of the total supply.
+#[derive(Default)]
+pub struct Supermajority {
+    support_threshold: Option<Ratio>,
+    min_participation: Option<Ratio>,
+}
+
+impl Supermajority {
+    pub fn new(support_threshold: Option<Ratio>, min_participation: Option<Ratio>) -> Self {
+        Self {
+            support_threshold,
+            min_participation,
+        }
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> ExecutionStrategy<T, N, P, H> for Supermajority
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn proof_execution(
+        &self,
+        _env: &mut EthHostEvmEnv<T, N, P, H>,
+        total_supply: U256,
+        tally: &[U256],
+    ) -> bool {
+        match (&self.support_threshold, &self.min_participation) {
+            (Some(support_threshold), Some(min_participation)) => {
+                strategies::execution_strategies::supermajority_reached(
+                    total_supply,
+                    tally,
+                    support_threshold,
+                    min_participation,
+                )
+            }
+            _ => false,
+        }
+    }
+}
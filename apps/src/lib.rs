@@ -16,27 +16,136 @@
 // transactions to a deployed app contract on Ethereum.
 
 pub mod delegation_strategies;
+pub mod execution;
 pub mod execution_strategies;
+pub mod proposal;
+pub mod prover;
+pub mod vote;
 pub mod voting_power_strategies;
-use alloy::{network::Network, providers::Provider, transports::Transport};
-use alloy_primitives::{Bytes, U256};
-use anyhow::{bail, Result};
+use alloy::{
+    network::{Ethereum, Network},
+    providers::{Provider, ProviderBuilder, RootProvider},
+    transports::{
+        http::{reqwest::Url, Client, Http},
+        Transport,
+    },
+};
+use alloy_primitives::{Address, Bytes, U256};
+use anyhow::{bail, ensure, Context, Result};
 use delegation_strategies::*;
 use execution_strategies::*;
 use risc0_steel::{
-    ethereum::EthEvmEnv,
+    ethereum::{EthBlockHeader, EthEvmEnv, EthEvmInput},
     host::{
         db::{AlloyDb, ProofDb},
         HostCommit,
     },
+    Commitment,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use strategies::{
+    chain_spec,
+    voting_strategies::{default_member_voting_power, lookback_block, MemberCall, MemberCountCall},
+};
 use voting_power_strategies::*;
 
+pub use execution::ExecutionProver;
+pub use proposal::ProposalProver;
+pub use risc0_zkvm::ProverOpts;
+pub use prover::ProverBuilder;
+pub use vote::VoteProver;
+
+alloy::sol! {
+    /// The proposal functions and vote events of the plugin. Enums are decoded as their
+    /// underlying `uint8`.
+    #[sol(rpc)]
+    interface IProposals {
+        struct ProposalParameters {
+            uint8 votingMode;
+            uint32 supportThreshold;
+            uint64 startDate;
+            uint64 endDate;
+            uint64 snapshotBlock;
+            bytes32 snapshotBlockHash;
+            string votingProtocolConfig;
+            address verifier;
+            bytes32 votingProtocolImageId;
+            bytes32 executionProtocolImageId;
+            uint64 snapshotBeaconTimestamp;
+            bytes32 snapshotBeaconRoot;
+            bytes32 votingProtocolConfigHash;
+            uint8 optionCount;
+        }
+
+        struct Tally {
+            uint256 abstain;
+            uint256 yes;
+            uint256 no;
+        }
+
+        /// Action executed by the DAO when the proposal passes, as in `IDAO.Action`.
+        #[derive(Debug, Deserialize)]
+        struct Action {
+            address to;
+            uint256 value;
+            bytes data;
+        }
+
+        struct ChainBlock {
+            uint64 chainId;
+            uint64 blockNumber;
+            bytes32 blockHash;
+        }
+
+        function getProposal(uint256 proposal_id) external view returns (
+            bool open,
+            bool executed,
+            ProposalParameters memory parameters,
+            Tally memory tally,
+            Action[] memory actions,
+            uint256 allowFailureMap
+        );
+
+        function createProposalWithProof(
+            bytes calldata journalData,
+            bytes calldata seal,
+            bytes calldata _metadata,
+            Action[] calldata _actions,
+            uint256 _allowFailureMap,
+            uint64 _startDate,
+            uint64 _endDate
+        ) external returns (uint256 proposalId);
+
+        /// DAO the plugin belongs to.
+        function dao() external view returns (address);
+
+        function getChainBlocks(uint256 proposal_id) external view returns (ChainBlock[] memory);
+
+        function currentChainBlocks() external view returns (ChainBlock[] memory);
+
+        /// `voteOption` is an `IMajorityVoting.VoteOption`. A split vote emits one event per
+        /// option it gives voting power to.
+        event VoteCast(uint256 indexed proposalId, address indexed voter, uint8 voteOption, uint256 votingPower);
+
+        event BallotCast(uint256 indexed proposalId, address indexed voter, uint8[] choices, uint256 votingPower);
+    }
+
+    #[sol(rpc)]
+    interface IVotingSettings {
+        function votingProtocolConfig() external view returns (string memory);
+        function votingProtocolConfigHash() external view returns (bytes32);
+        function minProposerVotingPower() external view returns (uint256);
+    }
+}
+
 //type HostEvmEnv<D, H, C> = EvmEnv<ProofDb<D>, H, HostCommit<C>>;
 type EthHostEvmEnv<T, N, P, C> = EthEvmEnv<ProofDb<AlloyDb<T, N, P>>, HostCommit<C>>;
 
+/// Host environment created with `EthEvmEnv::builder().rpc(..)`. `C` is `()` for block
+/// commitments and the beacon commit for beacon commitments.
+pub type RpcHostEvmEnv<C> = EthHostEvmEnv<Http<Client>, Ethereum, RootProvider<Http<Client>>, C>;
+
 /// Wrapper for the commit on the host.
 
 pub struct HostContext<'a, T, N, P, H>
@@ -50,6 +159,7 @@ where
     delegation_strategies: HashMap<String, Box<dyn DelegationStrategy<T, N, P, H>>>,
     execution_strategies: HashMap<String, Box<dyn ExecutionStrategy<T, N, P, H>>>,
     env: &'a mut EthHostEvmEnv<T, N, P, H>,
+    chain_envs: BTreeMap<u64, &'a mut EthHostEvmEnv<T, N, P, H>>,
 }
 
 impl<'a, T, N, P, H> HostContext<'a, T, N, P, H>
@@ -65,6 +175,15 @@ where
             HashMap::new();
         voting_power_strategies.insert("BalanceOf".to_string(), Box::new(BalanceOf));
         voting_power_strategies.insert("GetPastVotes".to_string(), Box::new(GetPastVotes));
+        voting_power_strategies.insert("Membership".to_string(), Box::new(Membership));
+        voting_power_strategies.insert(
+            "MerkleCensus".to_string(),
+            Box::new(MerkleCensus::default()),
+        );
+        voting_power_strategies.insert(
+            "MinBalanceOf".to_string(),
+            Box::new(MinBalanceOf::default()),
+        );
 
         let mut delegation_strategies: HashMap<String, Box<dyn DelegationStrategy<T, N, P, H>>> =
             HashMap::new();
@@ -73,27 +192,98 @@ where
         let mut execution_strategies: HashMap<String, Box<dyn ExecutionStrategy<T, N, P, H>>> =
             HashMap::new();
         execution_strategies.insert("MajorityVoting".to_string(), Box::new(MajorityVoting));
+        execution_strategies.insert(
+            "ApprovalVoting".to_string(),
+            Box::new(Ballots::approval_voting(None)),
+        );
+        execution_strategies.insert(
+            "RankedChoice".to_string(),
+            Box::new(Ballots::ranked_choice(None)),
+        );
+        execution_strategies.insert("Optimistic".to_string(), Box::new(Optimistic::default()));
+        execution_strategies.insert(
+            "Supermajority".to_string(),
+            Box::new(Supermajority::default()),
+        );
+        execution_strategies.insert(
+            "AbsoluteQuorum".to_string(),
+            Box::new(AbsoluteQuorum::default()),
+        );
 
         Self {
             voting_power_strategies,
             delegation_strategies,
             execution_strategies,
             env,
+            chain_envs: BTreeMap::new(),
         }
     }
 
+    pub fn add_voting_power_strategy(
+        &mut self,
+        name: String,
+        voting_power_strategy: Box<dyn VotingPowerStrategy<T, N, P, H>>,
+    ) {
+        self.voting_power_strategies
+            .insert(name, voting_power_strategy);
+    }
+
+    pub fn add_execution_strategy(
+        &mut self,
+        name: String,
+        execution_strategy: Box<dyn ExecutionStrategy<T, N, P, H>>,
+    ) {
+        self.execution_strategies.insert(name, execution_strategy);
+    }
+
+    /// Replaces the execution strategies taking parameters with ones using the config's, as the
+    /// guest does.
+    pub fn configure_execution_strategies(&mut self, config: &RiscVotingProtocolConfig) {
+        self.add_execution_strategy(
+            "Optimistic".to_string(),
+            Box::new(Optimistic::new(config.veto_threshold.clone())),
+        );
+        self.add_execution_strategy(
+            "Supermajority".to_string(),
+            Box::new(Supermajority::new(
+                config.support_threshold.clone(),
+                config.min_participation.clone(),
+            )),
+        );
+        self.add_execution_strategy(
+            "AbsoluteQuorum".to_string(),
+            Box::new(AbsoluteQuorum::new(config.quorum)),
+        );
+        self.add_execution_strategy(
+            "ApprovalVoting".to_string(),
+            Box::new(Ballots::approval_voting(config.min_participation.clone())),
+        );
+        self.add_execution_strategy(
+            "RankedChoice".to_string(),
+            Box::new(Ballots::ranked_choice(config.min_participation.clone())),
+        );
+    }
+
+    /// Adds the environment used for the assets living on `chain_id`. Assets on chains without
+    /// their own environment are evaluated against the main one.
+    pub fn add_chain_env(&mut self, chain_id: u64, env: &'a mut EthHostEvmEnv<T, N, P, H>) {
+        self.chain_envs.insert(chain_id, env);
+    }
+
     pub async fn process_voting_power_strategy(
         &mut self,
         name: String,
         account: alloy_primitives::Address,
         asset: &Asset,
-    ) -> alloy_primitives::U256 {
+    ) -> Result<U256> {
         if let Some(voting_power_strategy) = self.voting_power_strategies.get(&name) {
-            voting_power_strategy
-                .process(&mut self.env, account, asset)
-                .await
+            let env = match self.chain_envs.get_mut(&asset.chain_id) {
+                Some(chain_env) => &mut **chain_env,
+                None => &mut *self.env,
+            };
+            voting_power_strategy.process(env, account, asset).await
         } else {
-            panic!("Strategy not found: {}", name);
+            bail!("Strategy not found: {}", name);
         }
     }
 
@@ -107,8 +297,12 @@ where
             .delegation_strategies
             .get(asset.delegation.strategy.as_str())
         {
+            let env = match self.chain_envs.get_mut(&asset.chain_id) {
+                Some(chain_env) => &mut **chain_env,
+                None => &mut *self.env,
+            };
             delegation_strategy
-                .process(&mut self.env, account, asset, additional_data)
+                .process(env, account, asset, additional_data)
                 .await
         } else {
             panic!("Strategy not found: {}", asset.delegation.strategy);
@@ -120,7 +314,11 @@ where
             .voting_power_strategies
             .get(&asset.voting_power_strategy)
         {
-            Ok(voting_strategy.get_supply(&mut self.env, asset).await)
+            let env = match self.chain_envs.get_mut(&asset.chain_id) {
+                Some(chain_env) => &mut **chain_env,
+                None => &mut *self.env,
+            };
+            voting_strategy.get_supply(env, asset).await
         } else {
             bail!("Strategy not found: {}", &asset.voting_power_strategy);
         }
@@ -130,7 +328,7 @@ where
         &mut self,
         name: String,
         total_supply: U256,
-        tally: [U256; 3],
+        tally: &[U256],
     ) -> Result<bool> {
         if let Some(execution_strategy) = self.execution_strategies.get(&name) {
             Ok(execution_strategy
@@ -142,6 +340,220 @@ where
     }
 }
 
+/// Parses a `<CHAIN_ID>=<RPC_URL>` pair given on the command line.
+pub fn parse_chain_rpc_url(value: &str) -> Result<(u64, Url)> {
+    let (chain_id, rpc_url) = value
+        .split_once('=')
+        .context("expected <CHAIN_ID>=<RPC_URL>")?;
+    Ok((chain_id.parse()?, Url::parse(rpc_url)?))
+}
+
+/// Builds one environment per block of another chain pinned by the plugin. Every chain needs an
+/// RPC URL, and its block must have the pinned hash and not be newer than the snapshot
+/// `timestamp`, which the guests check too.
+pub async fn chain_envs(
+    chain_rpc_urls: &[(u64, Url)],
+    chain_blocks: &[IProposals::ChainBlock],
+    timestamp: u64,
+) -> Result<BTreeMap<u64, RpcHostEvmEnv<()>>> {
+    let mut envs = BTreeMap::new();
+    for chain_block in chain_blocks {
+        let chain_id = chain_block.chainId;
+        let rpc_url = chain_rpc_urls
+            .iter()
+            .find(|(id, _)| *id == chain_id)
+            .map(|(_, rpc_url)| rpc_url.clone())
+            .with_context(|| format!("no RPC URL for chain {}, pass --chain-rpc-url", chain_id))?;
+
+        let block = ProviderBuilder::new()
+            .on_http(rpc_url.clone())
+            .get_block_by_number(chain_block.blockNumber.into(), false)
+            .await?
+            .with_context(|| {
+                format!(
+                    "block {} of chain {} not found",
+                    chain_block.blockNumber, chain_id
+                )
+            })?;
+        ensure!(
+            block.header.hash == chain_block.blockHash,
+            "block {} of chain {} has hash {}, but the plugin pinned {}",
+            chain_block.blockNumber,
+            chain_id,
+            block.header.hash,
+            chain_block.blockHash
+        );
+        ensure!(
+            block.header.timestamp <= timestamp,
+            "block {} of chain {} is newer than the snapshot",
+            chain_block.blockNumber,
+            chain_id
+        );
+
+        let env = EthEvmEnv::builder()
+            .rpc(rpc_url)
+            .block_number(chain_block.blockNumber)
+            .build()
+            .await?;
+        envs.insert(chain_id, env.with_chain_spec(&chain_spec(chain_id)));
+    }
+    Ok(envs)
+}
+
+/// Turns the additional chain environments into guest inputs, ordered by chain id.
+pub async fn chain_inputs(
+    chain_envs: BTreeMap<u64, RpcHostEvmEnv<()>>,
+) -> Result<Vec<(u64, EthEvmInput)>> {
+    let mut inputs = Vec::new();
+    for (chain_id, env) in chain_envs {
+        inputs.push((chain_id, env.into_input().await?));
+    }
+    Ok(inputs)
+}
+
+/// Blocks of the look-back offsets of the config's assets, from the newest to the oldest.
+/// Offsets are counted back from `snapshot_block` and must lie in `1..=snapshot_block`.
+pub fn lookback_blocks(config: &RiscVotingProtocolConfig, snapshot_block: u64) -> Result<Vec<u64>> {
+    let blocks = config
+        .assets
+        .iter()
+        .flat_map(|asset| asset.lookback_blocks.iter())
+        .map(|offset| {
+            lookback_block(snapshot_block, *offset).with_context(|| {
+                format!(
+                    "look-back offset {} is outside 1..={}",
+                    offset, snapshot_block
+                )
+            })
+        })
+        .collect::<Result<BTreeSet<u64>>>()?;
+    Ok(blocks.into_iter().rev().collect())
+}
+
+/// Fetches the headers from block `from` down to block `to`, both inclusive, so the guest can
+/// link look-back blocks to the snapshot through their parent hashes.
+pub async fn ancestor_headers(rpc_url: Url, from: u64, to: u64) -> Result<Vec<EthBlockHeader>> {
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+
+    let mut headers = Vec::new();
+    for number in (to..=from).rev() {
+        let block = provider
+            .get_block_by_number(number.into(), false)
+            .await?
+            .with_context(|| format!("block {} not found", number))?;
+        let header: alloy::consensus::Header = block.header.try_into()?;
+        headers.push(EthBlockHeader::new(header));
+    }
+    Ok(headers)
+}
+
+/// Reads the parameters a proposal was created with. The proofs have to be made at its
+/// `snapshotBlock`, which is also where the guests read the plugin's config hash.
+pub async fn proposal_parameters(
+    rpc_url: Url,
+    config_contract: Address,
+    proposal_id: U256,
+) -> Result<IProposals::ProposalParameters> {
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+    let proposal = IProposals::new(config_contract, provider)
+        .getProposal(proposal_id)
+        .call()
+        .await?;
+    ensure!(
+        proposal.parameters.snapshotBlock != 0,
+        "proposal {} does not exist",
+        proposal_id
+    );
+    Ok(proposal.parameters)
+}
+
+/// Reads the blocks of other chains a proposal pinned at its creation, which its proofs have to
+/// commit to.
+pub async fn proposal_chain_blocks(
+    rpc_url: Url,
+    config_contract: Address,
+    proposal_id: U256,
+) -> Result<Vec<IProposals::ChainBlock>> {
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+    let chain_blocks = IProposals::new(config_contract, provider)
+        .getChainBlocks(proposal_id)
+        .call()
+        .await?;
+    Ok(chain_blocks._0)
+}
+
+/// Reads the blocks of other chains a proposal created now would pin.
+pub async fn current_chain_blocks(
+    rpc_url: Url,
+    config_contract: Address,
+) -> Result<Vec<IProposals::ChainBlock>> {
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+    let chain_blocks = IProposals::new(config_contract, provider)
+        .currentChainBlocks()
+        .call()
+        .await?;
+    Ok(chain_blocks._0)
+}
+
+/// Picks the block to prove a proposal at: its snapshot block, unless another one is given.
+pub fn snapshot_block_number(
+    parameters: &IProposals::ProposalParameters,
+    block_number: Option<u64>,
+) -> Result<u64> {
+    let snapshot_block = parameters.snapshotBlock;
+    match block_number {
+        Some(block_number) => {
+            ensure!(
+                block_number == snapshot_block,
+                "block {} is not the snapshot block {} of the proposal",
+                block_number,
+                snapshot_block
+            );
+            Ok(block_number)
+        }
+        None => Ok(snapshot_block),
+    }
+}
+
+/// Reads the plugin's current config, which applies to the proposals created now.
+pub async fn current_voting_protocol_config(
+    rpc_url: Url,
+    config_contract: Address,
+) -> Result<String> {
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+    let config = IVotingSettings::new(config_contract, provider)
+        .votingProtocolConfig()
+        .call()
+        .await?;
+    Ok(config._0)
+}
+
+/// Encodes a JSON config into the binary form taken by the guests. The guests hash the canonical
+/// JSON form of what they are given, so the config must already be in that form, as printed by
+/// `config_hash --canonical`.
+pub fn encode_config(config: &str) -> Result<Vec<u8>> {
+    let parsed = serde_json::from_str::<strategies::RiscVotingProtocolConfig>(config)
+        .context("invalid config")?;
+    ensure!(
+        parsed.to_json() == config,
+        "the config is not in canonical form, store the one printed by `config_hash --canonical`"
+    );
+    Ok(parsed.to_bytes())
+}
+
+/// Describes a Steel commitment decoded from a journal according to its version.
+pub fn describe_commitment(commitment: &Commitment) -> Result<String> {
+    let (id, version) = commitment.decode_id();
+    match version {
+        0 => Ok(format!("block {} with hash {}", id, commitment.digest)),
+        1 => Ok(format!(
+            "beacon block root {} at timestamp {}",
+            commitment.digest, id
+        )),
+        version => bail!("unsupported commitment version: {}", version),
+    }
+}
+
 // The input of the config
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -157,6 +569,14 @@ pub struct Asset {
     pub chain_id: u64,
     pub voting_power_strategy: String,
     pub delegation: DelegationObject,
+    #[serde(default)]
+    pub lookback_blocks: Vec<u64>,
+    #[serde(default)]
+    pub member_call: MemberCall,
+    #[serde(default)]
+    pub member_count_call: MemberCountCall,
+    #[serde(default = "default_member_voting_power")]
+    pub member_voting_power: U256,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -165,4 +585,119 @@ pub struct RiscVotingProtocolConfig {
     pub voting_protocol_version: String,
     pub assets: Vec<Asset>,
     pub execution_strategy: String,
+    #[serde(default)]
+    pub multi_option_execution_strategy: Option<String>,
+    #[serde(default)]
+    pub veto_threshold: Option<strategies::Ratio>,
+    #[serde(default)]
+    pub support_threshold: Option<strategies::Ratio>,
+    #[serde(default)]
+    pub min_participation: Option<strategies::Ratio>,
+    #[serde(default)]
+    pub quorum: Option<U256>,
+}
+
+impl RiscVotingProtocolConfig {
+    /// Execution strategy of the proposals with `option_count` options, as the guest picks it.
+    pub fn execution_strategy_for(&self, option_count: u8) -> Option<&str> {
+        if option_count == 0 {
+            Some(&self.execution_strategy)
+        } else {
+            self.multi_option_execution_strategy.as_deref()
+        }
+    }
+}
+
+// Unit tests module
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+
+    fn config(lookback_blocks: &[&[u64]]) -> RiscVotingProtocolConfig {
+        let assets = lookback_blocks
+            .iter()
+            .map(|offsets| {
+                serde_json::json!({
+                    "contract": Address::ZERO,
+                    "chainId": 11155111,
+                    "votingPowerStrategy": "MinBalanceOf",
+                    "delegation": { "contract": Address::ZERO, "strategy": "SplitDelegation" },
+                    "lookbackBlocks": offsets,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::from_value(serde_json::json!({
+            "votingProtocolVersion": "1",
+            "assets": assets,
+            "executionStrategy": "MajorityVoting",
+        }))
+        .unwrap()
+    }
+
+    fn commitment(version: u16, id: u64, digest: B256) -> Commitment {
+        Commitment {
+            id: (U256::from(version) << 240) | U256::from(id),
+            digest,
+        }
+    }
+
+    #[test]
+    fn test_describe_block_commitment() {
+        let description = describe_commitment(&commitment(0, 7087022, B256::repeat_byte(1)));
+        assert_eq!(
+            description.unwrap(),
+            format!("block 7087022 with hash {}", B256::repeat_byte(1))
+        );
+    }
+
+    #[test]
+    fn test_describe_beacon_commitment() {
+        let description = describe_commitment(&commitment(1, 1727000000, B256::repeat_byte(2)));
+        assert_eq!(
+            description.unwrap(),
+            format!(
+                "beacon block root {} at timestamp 1727000000",
+                B256::repeat_byte(2)
+            )
+        );
+    }
+
+    #[test]
+    fn test_describe_unsupported_commitment() {
+        let description = describe_commitment(&commitment(2, 1, B256::ZERO));
+        assert_eq!(
+            description.unwrap_err().to_string(),
+            "unsupported commitment version: 2"
+        );
+    }
+
+    #[test]
+    fn test_lookback_blocks() {
+        let blocks = lookback_blocks(&config(&[&[10, 1], &[10, 100]]), 100).unwrap();
+        assert_eq!(blocks, vec![99, 90, 0]);
+        assert!(lookback_blocks(&config(&[]), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_only_canonical_configs_are_encoded() {
+        let config = r#"{ "votingProtocolVersion": "1", "assets": [], "executionStrategy": "MajorityVoting" }"#;
+        assert!(encode_config(config).is_err());
+        assert!(encode_config("not a config").is_err());
+
+        let canonical = serde_json::from_str::<strategies::RiscVotingProtocolConfig>(config)
+            .unwrap()
+            .to_json();
+        let encoded = encode_config(&canonical).unwrap();
+        let decoded = strategies::RiscVotingProtocolConfig::from_bytes(&encoded).unwrap();
+        assert_eq!(decoded.to_json(), canonical);
+    }
+
+    #[test]
+    fn test_lookback_offsets_are_checked() {
+        assert!(lookback_blocks(&config(&[&[0]]), 100).is_err());
+        assert!(lookback_blocks(&config(&[&[1], &[101]]), 100).is_err());
+        assert!(lookback_blocks(&config(&[&[1]]), 0).is_err());
+    }
 }
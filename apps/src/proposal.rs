@@ -0,0 +1,450 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Proposals are created with a proof that their proposer holds `minProposerVotingPower`. The
+// proof commits to the proposal's parameters, so it can only create the proposal it was made for.
+
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+
+use alloy::{primitives::TxHash, sol_types::SolValue, transports::http::reqwest::Url};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use anyhow::{ensure, Context, Result};
+use aragon_zk_voting_protocol_methods::guest_images;
+use journal::ProposalJournal;
+use risc0_steel::{
+    ethereum::{EthBlockHeader, EthEvmEnv, EthEvmInput},
+    Contract,
+};
+use risc0_zkvm::sha::Digest;
+use strategies::{config_hash, input::ProposalInput, voting_strategies::CensusProof};
+use tokio::sync::Mutex;
+
+use crate::{
+    ancestor_headers, chain_envs, chain_inputs, current_chain_blocks,
+    current_voting_protocol_config, encode_config, lookback_blocks,
+    prover::{prove_input, Proof, ProverBuilder, ProverSettings, Submission},
+    voting_power_strategies::{MerkleCensus, MinBalanceOf},
+    HostContext,
+    IProposals::{self, Action},
+    IVotingSettings, RiscVotingProtocolConfig, RpcHostEvmEnv,
+};
+
+/// A proposal to create once its proposer's voting power is proven.
+#[derive(Clone, Debug)]
+pub struct ProposalRequest {
+    /// Block to prove at, the latest when unset. It must be one of the last 256 blocks when the
+    /// proposal is sent.
+    pub block_number: Option<u64>,
+    /// Account sending the proposal, which the plugin checks.
+    pub proposer: Address,
+    pub config_contract: Address,
+    pub additional_delegation_data: String,
+    pub census_proofs: Vec<CensusProof>,
+    pub metadata: Bytes,
+    pub actions: Vec<Action>,
+    /// Bitmap of the actions allowed to fail.
+    pub allow_failure_map: U256,
+    /// Start date of the vote, 0 to start immediately.
+    pub start_date: u64,
+    /// End date of the vote, 0 to use the minimum duration.
+    pub end_date: u64,
+}
+
+impl ProposalRequest {
+    /// Hash of the proposal's parameters, committed by the proof.
+    pub fn params_hash(&self) -> B256 {
+        proposal_params_hash(
+            &self.metadata,
+            &self.actions,
+            self.allow_failure_map,
+            self.start_date,
+            self.end_date,
+        )
+    }
+}
+
+/// Hashes the parameters of a proposal the way the plugin's `proposalParamsHash` does.
+pub fn proposal_params_hash(
+    metadata: &Bytes,
+    actions: &[Action],
+    allow_failure_map: U256,
+    start_date: u64,
+    end_date: u64,
+) -> B256 {
+    keccak256(
+        (
+            metadata.clone(),
+            actions.to_vec(),
+            allow_failure_map,
+            start_date,
+            end_date,
+        )
+            .abi_encode_params(),
+    )
+}
+
+/// Result of the preflight: everything the proposal guest needs.
+pub struct ProposalPreflight {
+    pub block_number: u64,
+    pub total_voting_power: U256,
+    /// Guest implementing the version of the plugin's current config.
+    pub elf: &'static [u8],
+    evm_input: EthEvmInput,
+    chain_inputs: Vec<(u64, EthEvmInput)>,
+    config: Vec<u8>,
+    ancestors: Vec<EthBlockHeader>,
+    lookback_inputs: Vec<EthEvmInput>,
+}
+
+impl ProposalPreflight {
+    /// Builds the guest input for `request`.
+    pub fn into_input(self, request: ProposalRequest) -> ProposalInput {
+        ProposalInput {
+            version: ProposalInput::VERSION,
+            evm_input: self.evm_input,
+            proposer: request.proposer,
+            params_hash: request.params_hash(),
+            config_contract: request.config_contract,
+            config: self.config,
+            additional_delegation_data: request.additional_delegation_data,
+            census_proofs: request.census_proofs,
+            ancestors: self.ancestors,
+            lookback_inputs: self.lookback_inputs,
+            chain_inputs: self.chain_inputs,
+        }
+    }
+}
+
+/// Preflights, proves and submits proposals. Built with [`ProverBuilder`].
+#[derive(Clone)]
+pub struct ProposalProver {
+    settings: ProverSettings,
+}
+
+/// Proof of a proposer's voting power.
+pub type ProposalProof = Proof<ProposalJournal>;
+
+impl ProposalProver {
+    pub(crate) fn new(settings: ProverSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn builder(rpc_url: Url) -> ProverBuilder {
+        ProverBuilder::new(rpc_url)
+    }
+
+    /// Preflights `request` under the plugin's current config. Fails if the proposer holds
+    /// less than `minProposerVotingPower`.
+    pub async fn preflight(&self, request: &ProposalRequest) -> Result<ProposalPreflight> {
+        let mut builder = EthEvmEnv::builder().rpc(self.settings.rpc_url.clone());
+        if let Some(block_number) = request.block_number {
+            builder = builder.block_number(block_number);
+        }
+
+        // The plugin pins the blocks its chain oracles report when the proposal is created, so
+        // the proof commits to those. It reverts if an oracle moves on before the proposal lands.
+        let chain_blocks =
+            current_chain_blocks(self.settings.rpc_url.clone(), request.config_contract).await?;
+
+        //  The `with_chain_spec` method is used to specify the chain configuration.
+        let (block_number, evm_input, chain_inputs, power) = match &self.settings.beacon_api_url {
+            Some(beacon_api_url) => {
+                let mut env = builder
+                    .beacon_api(beacon_api_url.clone())
+                    .build()
+                    .await?
+                    .with_chain_spec(&self.settings.chain_spec);
+                ensure!(
+                    chain_blocks.is_empty(),
+                    "cross-chain assets need block commitments, drop the beacon API URL"
+                );
+                let mut chain_envs = BTreeMap::new();
+                let power = preflight_proposer_voting_power(
+                    &mut env,
+                    &mut chain_envs,
+                    &self.settings,
+                    request,
+                )
+                .await?;
+                let block_number = env.header().number;
+                (block_number, env.into_input().await?, Vec::new(), power)
+            }
+            None => {
+                let mut env = builder
+                    .build()
+                    .await?
+                    .with_chain_spec(&self.settings.chain_spec);
+                let mut chain_envs = chain_envs(
+                    &self.settings.chain_rpc_urls,
+                    &chain_blocks,
+                    env.header().timestamp,
+                )
+                .await?;
+                let power = preflight_proposer_voting_power(
+                    &mut env,
+                    &mut chain_envs,
+                    &self.settings,
+                    request,
+                )
+                .await?;
+                let block_number = env.header().number;
+                (
+                    block_number,
+                    env.into_input().await?,
+                    chain_inputs(chain_envs).await?,
+                    power,
+                )
+            }
+        };
+
+        Ok(ProposalPreflight {
+            block_number,
+            total_voting_power: power.total_voting_power,
+            elf: power.elf,
+            evm_input,
+            chain_inputs,
+            config: power.config,
+            ancestors: power.ancestors,
+            lookback_inputs: power.lookback_inputs,
+        })
+    }
+
+    /// Proves the proposer's voting power for a preflighted request.
+    pub async fn prove(
+        &self,
+        preflight: ProposalPreflight,
+        request: ProposalRequest,
+    ) -> Result<ProposalProof> {
+        let elf = preflight.elf;
+        let input = preflight.into_input(request);
+        let (journal_bytes, seal) =
+            prove_input(elf, input, self.settings.prover_opts.clone()).await?;
+        let journal =
+            ProposalJournal::abi_decode(&journal_bytes, true).context("invalid journal")?;
+        Ok(Proof {
+            journal,
+            journal_bytes,
+            seal,
+        })
+    }
+
+    /// Creates the proposal of `request` with its proof, without waiting for its confirmation.
+    /// The wallet must be the proposer's.
+    pub async fn send(&self, proof: &ProposalProof, request: &ProposalRequest) -> Result<TxHash> {
+        ensure!(
+            proof.journal.paramsHash == request.params_hash(),
+            "the proof was made for other proposal parameters"
+        );
+        let provider = self.settings.wallet_provider()?;
+        let contract = IProposals::new(proof.journal.configContract, &provider);
+        let pending_tx = contract
+            .createProposalWithProof(
+                proof.journal_bytes.clone(),
+                proof.seal.clone(),
+                request.metadata.clone(),
+                request.actions.clone(),
+                request.allow_failure_map,
+                request.start_date,
+                request.end_date,
+            )
+            .send()
+            .await?;
+        Ok(*pending_tx.tx_hash())
+    }
+
+    /// Waits for a transaction returned by [`Self::send`] to be confirmed.
+    pub async fn confirm(&self, tx_hash: TxHash) -> Result<Submission> {
+        self.settings.confirm(tx_hash).await
+    }
+
+    /// Creates the proposal of `request` with its proof and waits for its confirmation.
+    pub async fn submit(
+        &self,
+        proof: &ProposalProof,
+        request: &ProposalRequest,
+    ) -> Result<Submission> {
+        let tx_hash = self.send(proof, request).await?;
+        self.confirm(tx_hash).await
+    }
+}
+
+/// Proposer voting power preflight needed to build the guest input.
+struct PowerPreflight {
+    total_voting_power: U256,
+    config: Vec<u8>,
+    elf: &'static [u8],
+    ancestors: Vec<EthBlockHeader>,
+    lookback_inputs: Vec<EthEvmInput>,
+}
+
+/// Preflights the config and voting power calls of the proposer on `env`, which can commit
+/// either to the block or to its beacon block root.
+async fn preflight_proposer_voting_power<C>(
+    env: &mut RpcHostEvmEnv<C>,
+    chain_envs: &mut BTreeMap<u64, RpcHostEvmEnv<C>>,
+    settings: &ProverSettings,
+    request: &ProposalRequest,
+) -> Result<PowerPreflight>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    let config_string =
+        current_voting_protocol_config(settings.rpc_url.clone(), request.config_contract).await?;
+    let config = serde_json::from_str::<RiscVotingProtocolConfig>(&config_string)?;
+
+    // The proposal is proven with the guest implementing the version of the current config.
+    let images = guest_images(&config.voting_protocol_version).with_context(|| {
+        format!(
+            "no guest for votingProtocolVersion {}",
+            config.voting_protocol_version
+        )
+    })?;
+    log::info!("Guest image ID: {}", Digest::from(images.proposal_id));
+
+    // Making the preflighs. This step is mandatory. There is no proposal yet, so the plugin's
+    // current config and threshold apply.
+    let mut config_contract = Contract::preflight(request.config_contract, env);
+    let config_hash_returns = config_contract
+        .call_builder(&IVotingSettings::votingProtocolConfigHashCall {})
+        .call()
+        .await?;
+    ensure!(
+        config_hash(&config_string) == config_hash_returns._0,
+        "config hashes to {}, but the plugin stores {}",
+        config_hash(&config_string),
+        config_hash_returns._0
+    );
+    let min_proposer_voting_power = config_contract
+        .call_builder(&IVotingSettings::minProposerVotingPowerCall {})
+        .call()
+        .await?
+        ._0;
+
+    // Look-back blocks of the assets using the `MinBalanceOf` strategy.
+    let block_number = env.header().number;
+    let lookback_blocks = lookback_blocks(&config, block_number)?;
+    let mut lookback_envs = Vec::new();
+    for number in &lookback_blocks {
+        let lookback_env = EthEvmEnv::builder()
+            .rpc(settings.rpc_url.clone())
+            .block_number(*number)
+            .build()
+            .await?;
+        lookback_envs.push(lookback_env.with_chain_spec(&settings.chain_spec));
+    }
+    let ancestors = match lookback_blocks.last() {
+        Some(oldest) => {
+            ancestor_headers(settings.rpc_url.clone(), block_number - 1, *oldest).await?
+        }
+        None => Vec::new(),
+    };
+    let lookback_envs = Arc::new(Mutex::new(lookback_envs));
+
+    // Assets on other chains are evaluated against their own environment.
+    for asset in &config.assets {
+        ensure!(
+            asset.chain_id == settings.chain_spec.chain_id()
+                || chain_envs.contains_key(&asset.chain_id),
+            "no environment for chain {}, cross-chain assets need --chain-rpc-url and block commitments",
+            asset.chain_id
+        );
+    }
+
+    let mut strategies_context = HostContext::default(env);
+    for (chain_id, chain_env) in chain_envs.iter_mut() {
+        strategies_context.add_chain_env(*chain_id, chain_env);
+    }
+    strategies_context.add_voting_power_strategy(
+        "MerkleCensus".to_string(),
+        Box::new(MerkleCensus::new(request.census_proofs.clone())),
+    );
+    strategies_context.add_voting_power_strategy(
+        "MinBalanceOf".to_string(),
+        Box::new(MinBalanceOf::new(lookback_envs.clone())),
+    );
+
+    // Get the total voting power of the proposer across all assets.
+    let additional_delegation_data = Bytes::from_str(request.additional_delegation_data.as_str())
+        .context("invalid additional delegation data")?;
+    let mut total_voting_power = U256::from(0);
+
+    for asset in &config.assets {
+        let delegations = strategies_context
+            .process_delegation_strategy(
+                request.proposer,
+                asset,
+                additional_delegation_data.clone(),
+            )
+            .await
+            .context("delegations given are not correct")?;
+
+        for delegation in &delegations {
+            let voting_power = strategies_context
+                .process_voting_power_strategy(
+                    asset.voting_power_strategy.clone(),
+                    delegation.delegate,
+                    asset,
+                )
+                .await?;
+
+            total_voting_power += voting_power / delegation.ratio;
+        }
+    }
+
+    ensure!(
+        total_voting_power >= min_proposer_voting_power,
+        "proposer holds {} voting power, but {} is required",
+        total_voting_power,
+        min_proposer_voting_power
+    );
+
+    drop(strategies_context);
+    let lookback_envs = Arc::try_unwrap(lookback_envs)
+        .ok()
+        .context("look-back environments are still in use")?
+        .into_inner();
+    let mut lookback_inputs = Vec::new();
+    for lookback_env in lookback_envs {
+        lookback_inputs.push(lookback_env.into_input().await?);
+    }
+
+    Ok(PowerPreflight {
+        total_voting_power,
+        config: encode_config(&config_string)?,
+        elf: images.proposal_elf,
+        ancestors,
+        lookback_inputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, b256, bytes};
+
+    use super::*;
+
+    #[test]
+    fn test_proposal_params_hash() {
+        // Pinned to the value `proposalParamsHash` returns in `RiscVotingProtocolPluginTest`.
+        let actions = vec![Action {
+            to: address!("0000000000000000000000000000000000000001"),
+            value: U256::from(5),
+            data: bytes!("abcd"),
+        }];
+        assert_eq!(
+            proposal_params_hash(&bytes!("1234"), &actions, U256::from(1), 0, 0),
+            b256!("64417dfd13b8018c00fcd8d96053a9d25b025a7b1aa6b2a4d44c1c6fb610cde9")
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Settings and steps shared by the vote, execution and proposal provers: proving a guest input
+// and sending the resulting proof to the plugin.
+
+use std::time::Duration;
+
+use alloy::{
+    network::{Ethereum, EthereumWallet},
+    primitives::TxHash,
+    providers::{Provider, ProviderBuilder},
+    transports::http::{reqwest::Url, Client, Http},
+};
+use alloy_primitives::Bytes;
+use anyhow::{bail, ensure, Context, Result};
+use risc0_ethereum_contracts::encode_seal;
+use risc0_steel::{config::ChainSpec, ethereum::ETH_SEPOLIA_CHAIN_SPEC};
+use risc0_zkvm::{default_prover, ExecutorEnv, ProveInfo, ProverOpts, VerifierContext};
+use serde::Serialize;
+use tokio::task;
+
+use crate::{execution::ExecutionProver, proposal::ProposalProver, vote::VoteProver};
+
+alloy::sol!(
+    #[sol(rpc, all_derives)]
+    "../contracts/IMajorityVoting.sol"
+);
+
+/// How often and how many times a sent transaction is checked for its receipt.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const CONFIRMATION_POLLS: usize = 300;
+
+/// Settings of a [`VoteProver`], an [`ExecutionProver`] or a [`ProposalProver`].
+#[derive(Clone)]
+pub(crate) struct ProverSettings {
+    pub(crate) rpc_url: Url,
+    pub(crate) beacon_api_url: Option<Url>,
+    pub(crate) chain_rpc_urls: Vec<(u64, Url)>,
+    pub(crate) chain_spec: ChainSpec,
+    pub(crate) prover_opts: ProverOpts,
+    pub(crate) wallet: Option<EthereumWallet>,
+}
+
+impl ProverSettings {
+    /// Waits until `tx_hash` is mined and checks that it succeeded.
+    pub(crate) async fn confirm(&self, tx_hash: TxHash) -> Result<Submission> {
+        let provider = ProviderBuilder::new().on_http(self.rpc_url.clone());
+        for _ in 0..CONFIRMATION_POLLS {
+            if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? {
+                ensure!(receipt.status(), "transaction failed: {}", tx_hash);
+                return Ok(Submission {
+                    tx_hash,
+                    block_number: receipt.block_number,
+                });
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+        bail!("transaction did not confirm: {}", tx_hash)
+    }
+
+    /// Provider signing the transactions sent to the plugin.
+    pub(crate) fn wallet_provider(&self) -> Result<impl Provider<Http<Client>, Ethereum>> {
+        let wallet = self
+            .wallet
+            .clone()
+            .context("a wallet is needed to submit proofs")?;
+        Ok(ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_http(self.rpc_url.clone()))
+    }
+}
+
+/// Builds a [`VoteProver`], an [`ExecutionProver`] or a [`ProposalProver`].
+#[derive(Clone)]
+pub struct ProverBuilder {
+    settings: ProverSettings,
+}
+
+impl ProverBuilder {
+    /// Starts from the RPC endpoint of the chain the plugin lives on. Proofs default to Groth16,
+    /// which the plugin's verifier accepts.
+    pub fn new(rpc_url: Url) -> Self {
+        Self {
+            settings: ProverSettings {
+                rpc_url,
+                beacon_api_url: None,
+                chain_rpc_urls: Vec::new(),
+                chain_spec: ETH_SEPOLIA_CHAIN_SPEC.clone(),
+                prover_opts: ProverOpts::groth16(),
+                wallet: None,
+            },
+        }
+    }
+
+    /// Commits the proofs to the snapshot's beacon block root (EIP-4788) instead of its hash.
+    pub fn beacon_api_url(mut self, beacon_api_url: Url) -> Self {
+        self.settings.beacon_api_url = Some(beacon_api_url);
+        self
+    }
+
+    /// Adds the RPC endpoint of another chain holding assets.
+    pub fn chain_rpc_url(mut self, chain_id: u64, rpc_url: Url) -> Self {
+        self.settings.chain_rpc_urls.push((chain_id, rpc_url));
+        self
+    }
+
+    /// Chain spec of the chain the plugin lives on. It must match the one of the guests.
+    pub fn chain_spec(mut self, chain_spec: ChainSpec) -> Self {
+        self.settings.chain_spec = chain_spec;
+        self
+    }
+
+    pub fn prover_opts(mut self, prover_opts: ProverOpts) -> Self {
+        self.settings.prover_opts = prover_opts;
+        self
+    }
+
+    /// Wallet paying for the transactions sent by `submit`.
+    pub fn wallet(mut self, wallet: EthereumWallet) -> Self {
+        self.settings.wallet = Some(wallet);
+        self
+    }
+
+    pub fn build_vote_prover(self) -> VoteProver {
+        VoteProver::new(self.settings)
+    }
+
+    pub fn build_execution_prover(self) -> ExecutionProver {
+        ExecutionProver::new(self.settings)
+    }
+
+    pub fn build_proposal_prover(self) -> ProposalProver {
+        ProposalProver::new(self.settings)
+    }
+}
+
+/// A proof ready to be sent to the plugin, along with its decoded journal `J`.
+pub struct Proof<J> {
+    pub journal: J,
+    pub journal_bytes: Bytes,
+    pub seal: Bytes,
+}
+
+/// A transaction of the plugin that was confirmed.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+    pub tx_hash: TxHash,
+    pub block_number: Option<u64>,
+}
+
+/// Proves `input` with the guest `elf` and returns the journal and the ABI encoded seal.
+pub(crate) async fn prove_input<I>(
+    elf: &'static [u8],
+    input: I,
+    prover_opts: ProverOpts,
+) -> Result<(Bytes, Bytes)>
+where
+    I: Serialize + Send + 'static,
+{
+    let prove_info = task::spawn_blocking(move || -> Result<ProveInfo> {
+        let env = ExecutorEnv::builder().write(&input)?.build()?;
+        default_prover().prove_with_ctx(env, &VerifierContext::default(), elf, &prover_opts)
+    })
+    .await?
+    .context("failed to create proof")?;
+
+    let receipt = prove_info.receipt;
+    let seal = encode_seal(&receipt).context("invalid receipt")?;
+    Ok((receipt.journal.bytes.into(), seal.into()))
+}
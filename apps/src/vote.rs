@@ -0,0 +1,606 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Votes are proven in two phases. The preflight evaluates the voter's power at the proposal's
+// snapshot block and returns it with the message to sign (the quote). Once signed, the preflight
+// becomes the input of the voting guest, whose proof is then sent to the plugin.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+    sync::Arc,
+};
+
+use alloy::{primitives::TxHash, sol_types::SolValue, transports::http::reqwest::Url};
+use alloy_primitives::{Address, Bytes, Signature, B256, U256};
+use anyhow::{ensure, Context, Result};
+use aragon_zk_voting_protocol_methods::guest_images;
+use journal::VotingJournal;
+use risc0_steel::{
+    ethereum::{EthBlockHeader, EthEvmEnv, EthEvmInput},
+    Contract,
+};
+use risc0_zkvm::sha::Digest;
+use serde::Serialize;
+use strategies::{
+    config_hash, execution_strategies::MAX_BALLOT_CHOICES, input::VotingInput,
+    voting_strategies::CensusProof,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    ancestor_headers, chain_envs, chain_inputs, encode_config, lookback_blocks,
+    proposal_chain_blocks, proposal_parameters,
+    prover::{prove_input, IMajorityVoting, Proof, ProverBuilder, ProverSettings, Submission},
+    snapshot_block_number,
+    voting_power_strategies::{MerkleCensus, MinBalanceOf},
+    HostContext,
+    IProposals::ProposalParameters,
+    IVotingSettings, RiscVotingProtocolConfig, RpcHostEvmEnv,
+};
+
+/// A vote as chosen by the voter, before its voting power is known.
+#[derive(Clone, Debug)]
+pub struct VoteRequest {
+    /// Must be the proposal's snapshot block when set.
+    pub block_number: Option<u64>,
+    pub voter: Address,
+    pub dao: Address,
+    pub proposal_id: U256,
+    /// Vote option as in `IMajorityVoting.VoteOption`, or 0 for a split vote or a ballot.
+    pub direction: u8,
+    /// Voting power given to abstain, yes and no by a split vote, empty otherwise.
+    pub amounts: Vec<U256>,
+    /// Options chosen by a ballot of a multi-option proposal, most preferred first.
+    pub choices: Vec<u8>,
+    pub config_contract: Address,
+    pub additional_delegation_data: String,
+    pub census_proofs: Vec<CensusProof>,
+}
+
+/// The exact voting power of a vote and the message the voter signs for it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteQuote {
+    pub block_number: u64,
+    pub balance: U256,
+    /// Hash of the vote, signed as a 32 byte EIP-191 personal message.
+    pub message: B256,
+}
+
+/// Result of the preflight: everything the voting guest needs but the voter's signature.
+pub struct VotePreflight {
+    pub quote: VoteQuote,
+    /// Guest implementing the version of the proposal's config.
+    pub elf: &'static [u8],
+    evm_input: EthEvmInput,
+    chain_inputs: Vec<(u64, EthEvmInput)>,
+    config: Vec<u8>,
+    ancestors: Vec<EthBlockHeader>,
+    lookback_inputs: Vec<EthEvmInput>,
+}
+
+impl VotePreflight {
+    /// Builds the guest input for `request` once the voter signed `quote.message`.
+    pub fn into_input(self, request: VoteRequest, signature: String) -> VotingInput {
+        VotingInput {
+            version: VotingInput::VERSION,
+            evm_input: self.evm_input,
+            signature,
+            voter: request.voter,
+            dao: request.dao,
+            proposal_id: request.proposal_id,
+            direction: request.direction,
+            amounts: request.amounts,
+            choices: request.choices,
+            balance: self.quote.balance,
+            config_contract: request.config_contract,
+            config: self.config,
+            additional_delegation_data: request.additional_delegation_data,
+            census_proofs: request.census_proofs,
+            ancestors: self.ancestors,
+            lookback_inputs: self.lookback_inputs,
+            chain_inputs: self.chain_inputs,
+        }
+    }
+}
+
+/// Hashes a vote the way the voting guest does before recovering the signer. Shared with the
+/// guest through the `strategies` crate.
+pub use strategies::vote_message;
+
+/// Recovers the address that signed `message` as a 32 byte EIP-191 personal message, as the
+/// voting guest does.
+pub fn recover_signer(message: B256, signature: &Signature) -> Result<Address> {
+    signature
+        .recover_address_from_msg(message)
+        .context("invalid signature")
+}
+
+/// Preflights, proves and submits votes. Built with [`ProverBuilder`].
+#[derive(Clone)]
+pub struct VoteProver {
+    settings: ProverSettings,
+}
+
+/// Proof of a vote.
+pub type VoteProof = Proof<VotingJournal>;
+
+impl VoteProver {
+    pub(crate) fn new(settings: ProverSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn builder(rpc_url: Url) -> ProverBuilder {
+        ProverBuilder::new(rpc_url)
+    }
+
+    /// RPC endpoints of the other chains holding assets.
+    pub fn chain_rpc_urls(&self) -> &[(u64, Url)] {
+        &self.settings.chain_rpc_urls
+    }
+
+    /// Returns the voting power of `request` and the message to sign, without keeping the
+    /// inputs.
+    pub async fn quote(&self, request: &VoteRequest) -> Result<VoteQuote> {
+        Ok(self.preflight(request).await?.quote)
+    }
+
+    /// Preflights `request` at the proposal's snapshot block.
+    pub async fn preflight(&self, request: &VoteRequest) -> Result<VotePreflight> {
+        check_request(request)?;
+
+        // The plugin validates the commitment against the proposal's snapshot block, so the proof
+        // is made there.
+        let parameters = proposal_parameters(
+            self.settings.rpc_url.clone(),
+            request.config_contract,
+            request.proposal_id,
+        )
+        .await?;
+        let block_number = snapshot_block_number(&parameters, request.block_number)?;
+        let chain_blocks = proposal_chain_blocks(
+            self.settings.rpc_url.clone(),
+            request.config_contract,
+            request.proposal_id,
+        )
+        .await?;
+
+        let builder = EthEvmEnv::builder()
+            .rpc(self.settings.rpc_url.clone())
+            .block_number(block_number);
+
+        //  The `with_chain_spec` method is used to specify the chain configuration.
+        let (evm_input, chain_inputs, power) = match &self.settings.beacon_api_url {
+            Some(beacon_api_url) => {
+                let mut env = builder
+                    .beacon_api(beacon_api_url.clone())
+                    .build()
+                    .await?
+                    .with_chain_spec(&self.settings.chain_spec);
+                ensure!(
+                    chain_blocks.is_empty(),
+                    "cross-chain assets need block commitments, drop the beacon API URL"
+                );
+                let mut chain_envs = BTreeMap::new();
+                let power = preflight_voting_power(
+                    &mut env,
+                    &mut chain_envs,
+                    &self.settings,
+                    request,
+                    &parameters,
+                )
+                .await?;
+                (env.into_input().await?, Vec::new(), power)
+            }
+            None => {
+                let mut env = builder
+                    .build()
+                    .await?
+                    .with_chain_spec(&self.settings.chain_spec);
+                let mut chain_envs = chain_envs(
+                    &self.settings.chain_rpc_urls,
+                    &chain_blocks,
+                    env.header().timestamp,
+                )
+                .await?;
+                let power = preflight_voting_power(
+                    &mut env,
+                    &mut chain_envs,
+                    &self.settings,
+                    request,
+                    &parameters,
+                )
+                .await?;
+                (
+                    env.into_input().await?,
+                    chain_inputs(chain_envs).await?,
+                    power,
+                )
+            }
+        };
+
+        check_amounts(request, power.total_voting_power)?;
+        let message = vote_message(
+            self.settings.chain_spec.chain_id(),
+            request.dao,
+            request.proposal_id,
+            request.direction,
+            &request.amounts,
+            &request.choices,
+            power.total_voting_power,
+        );
+
+        Ok(VotePreflight {
+            quote: VoteQuote {
+                block_number,
+                balance: power.total_voting_power,
+                message,
+            },
+            elf: power.elf,
+            evm_input,
+            chain_inputs,
+            config: power.config,
+            ancestors: power.ancestors,
+            lookback_inputs: power.lookback_inputs,
+        })
+    }
+
+    /// Proves a vote once the voter signed the quoted message.
+    pub async fn prove(
+        &self,
+        preflight: VotePreflight,
+        request: VoteRequest,
+        signature: String,
+    ) -> Result<VoteProof> {
+        let elf = preflight.elf;
+        let input = preflight.into_input(request, signature);
+        let (journal_bytes, seal) =
+            prove_input(elf, input, self.settings.prover_opts.clone()).await?;
+        let journal = VotingJournal::abi_decode(&journal_bytes, true).context("invalid journal")?;
+        Ok(Proof {
+            journal,
+            journal_bytes,
+            seal,
+        })
+    }
+
+    /// Sends the vote to the plugin it was proven for, without waiting for its confirmation.
+    pub async fn send(&self, proof: &VoteProof) -> Result<TxHash> {
+        let provider = self.settings.wallet_provider()?;
+        let contract = IMajorityVoting::new(proof.journal.configContract, &provider);
+        let pending_tx = contract
+            .vote(proof.journal_bytes.clone(), proof.seal.clone())
+            .send()
+            .await?;
+        Ok(*pending_tx.tx_hash())
+    }
+
+    /// Waits for a transaction returned by [`Self::send`] to be confirmed.
+    pub async fn confirm(&self, tx_hash: TxHash) -> Result<Submission> {
+        self.settings.confirm(tx_hash).await
+    }
+
+    /// Sends the vote to the plugin it was proven for and waits for its confirmation.
+    pub async fn submit(&self, proof: &VoteProof) -> Result<Submission> {
+        let tx_hash = self.send(proof).await?;
+        self.confirm(tx_hash).await
+    }
+}
+
+/// Checks the shape of a vote, before its voting power is known.
+pub fn check_request(request: &VoteRequest) -> Result<()> {
+    if !request.choices.is_empty() {
+        ensure!(
+            request.direction == 0 && request.amounts.is_empty(),
+            "ballots (choices) take direction 0 and no amounts"
+        );
+        ensure!(
+            request.choices.len() <= MAX_BALLOT_CHOICES,
+            "a ballot lists at most {} choices",
+            MAX_BALLOT_CHOICES
+        );
+        let mut chosen = BTreeSet::new();
+        ensure!(
+            request
+                .choices
+                .iter()
+                .all(|choice| *choice != 0 && chosen.insert(*choice)),
+            "choices must be distinct options, numbered from 1"
+        );
+    } else if request.direction == 0 {
+        ensure!(
+            request.amounts.len() == 3,
+            "a split vote needs amounts for abstain, yes and no"
+        );
+    } else {
+        ensure!(
+            request.direction <= 3,
+            "direction must be 1 (abstain), 2 (yes), 3 (no), or 0 for a split vote or a ballot"
+        );
+        ensure!(
+            request.amounts.is_empty(),
+            "only split votes (direction 0) take amounts"
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the amounts of a split vote add up to the voting power it was quoted.
+fn check_amounts(request: &VoteRequest, balance: U256) -> Result<()> {
+    if request.choices.is_empty() && request.direction == 0 {
+        let total = request
+            .amounts
+            .iter()
+            .try_fold(U256::from(0), |acc, amount| acc.checked_add(*amount));
+        ensure!(
+            total == Some(balance),
+            "split vote amounts must add up to the voting power of {}",
+            balance
+        );
+    }
+    Ok(())
+}
+
+/// Voting power preflight needed to build the guest input.
+struct PowerPreflight {
+    total_voting_power: U256,
+    config: Vec<u8>,
+    elf: &'static [u8],
+    ancestors: Vec<EthBlockHeader>,
+    lookback_inputs: Vec<EthEvmInput>,
+}
+
+/// Preflights the config and voting power calls on `env`, which can commit either to the
+/// snapshot block or to its beacon block root.
+async fn preflight_voting_power<C>(
+    env: &mut RpcHostEvmEnv<C>,
+    chain_envs: &mut BTreeMap<u64, RpcHostEvmEnv<C>>,
+    settings: &ProverSettings,
+    request: &VoteRequest,
+    parameters: &ProposalParameters,
+) -> Result<PowerPreflight>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    let config_string = &parameters.votingProtocolConfig;
+    let config = serde_json::from_str::<RiscVotingProtocolConfig>(config_string)?;
+    ensure!(
+        config_hash(config_string) == parameters.votingProtocolConfigHash,
+        "config hashes to {}, but the proposal stores {}",
+        config_hash(config_string),
+        parameters.votingProtocolConfigHash
+    );
+
+    // Proposals are proven with the guest implementing the version of their config.
+    let images = guest_images(&config.voting_protocol_version).with_context(|| {
+        format!(
+            "no guest for votingProtocolVersion {}",
+            config.voting_protocol_version
+        )
+    })?;
+    log::info!("Guest image ID: {}", Digest::from(images.voting_id));
+
+    // Making the preflighs. This step is mandatory. The proposal is created after its snapshot
+    // block, so the guest reads the plugin's config hash, which the proposal copied.
+    let config_hash_call = IVotingSettings::votingProtocolConfigHashCall {};
+    let mut config_contract = Contract::preflight(request.config_contract, env);
+    let config_hash_returns = config_contract
+        .call_builder(&config_hash_call)
+        .call()
+        .await?;
+    ensure!(
+        config_hash(config_string) == config_hash_returns._0,
+        "the plugin's config changed in the block creating the proposal, its hash was {}",
+        config_hash_returns._0
+    );
+
+    // Look-back blocks of the assets using the `MinBalanceOf` strategy.
+    let snapshot_block = parameters.snapshotBlock;
+    let lookback_blocks = lookback_blocks(&config, snapshot_block)?;
+    let mut lookback_envs = Vec::new();
+    for number in &lookback_blocks {
+        let lookback_env = EthEvmEnv::builder()
+            .rpc(settings.rpc_url.clone())
+            .block_number(*number)
+            .build()
+            .await?;
+        lookback_envs.push(lookback_env.with_chain_spec(&settings.chain_spec));
+    }
+    let ancestors = match lookback_blocks.last() {
+        Some(oldest) => {
+            ancestor_headers(settings.rpc_url.clone(), snapshot_block - 1, *oldest).await?
+        }
+        None => Vec::new(),
+    };
+    let lookback_envs = Arc::new(Mutex::new(lookback_envs));
+
+    // Assets on other chains are evaluated against their own environment.
+    for asset in &config.assets {
+        ensure!(
+            asset.chain_id == settings.chain_spec.chain_id()
+                || chain_envs.contains_key(&asset.chain_id),
+            "no environment for chain {}, cross-chain assets need --chain-rpc-url and block commitments",
+            asset.chain_id
+        );
+    }
+
+    let mut strategies_context = HostContext::default(env);
+    for (chain_id, chain_env) in chain_envs.iter_mut() {
+        strategies_context.add_chain_env(*chain_id, chain_env);
+    }
+    strategies_context.add_voting_power_strategy(
+        "MerkleCensus".to_string(),
+        Box::new(MerkleCensus::new(request.census_proofs.clone())),
+    );
+    strategies_context.add_voting_power_strategy(
+        "MinBalanceOf".to_string(),
+        Box::new(MinBalanceOf::new(lookback_envs.clone())),
+    );
+
+    // Get the total voting power of the voter across all assets.
+    let additional_delegation_data = Bytes::from_str(request.additional_delegation_data.as_str())
+        .context("invalid additional delegation data")?;
+    let mut total_voting_power = U256::from(0);
+
+    for asset in &config.assets {
+        let delegations = strategies_context
+            .process_delegation_strategy(request.voter, asset, additional_delegation_data.clone())
+            .await
+            .context("Delegations given are not correct")?;
+        let mut asset_voting_power = U256::from(0);
+
+        for delegation in &delegations {
+            let strategy = asset.voting_power_strategy.clone();
+            let delegate = delegation.delegate;
+            let ratio = delegation.ratio;
+
+            // Call the async function and await the result
+            let voting_power = strategies_context
+                .process_voting_power_strategy(strategy, delegate, asset)
+                .await?;
+
+            asset_voting_power += voting_power / ratio;
+        }
+        total_voting_power += asset_voting_power;
+    }
+
+    drop(strategies_context);
+    let lookback_envs = Arc::try_unwrap(lookback_envs)
+        .ok()
+        .context("look-back environments are still in use")?
+        .into_inner();
+    let mut lookback_inputs = Vec::new();
+    for lookback_env in lookback_envs {
+        lookback_inputs.push(lookback_env.into_input().await?);
+    }
+
+    Ok(PowerPreflight {
+        total_voting_power,
+        config: encode_config(config_string)?,
+        elf: images.voting_elf,
+        ancestors,
+        lookback_inputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, b256, eip191_hash_message, keccak256};
+
+    use super::*;
+
+    fn request(direction: u8, amounts: &[u64], choices: &[u8]) -> VoteRequest {
+        VoteRequest {
+            block_number: None,
+            voter: Address::ZERO,
+            dao: Address::ZERO,
+            proposal_id: U256::ZERO,
+            direction,
+            amounts: amounts.iter().map(|amount| U256::from(*amount)).collect(),
+            choices: choices.to_vec(),
+            config_contract: Address::ZERO,
+            additional_delegation_data: String::new(),
+            census_proofs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_vote_message_is_pinned() {
+        // Digests the voting guest's `hash_vote` recovers the signer from, for a vote, a split
+        // vote and a ballot. Changing the message invalidates every signature made before.
+        let dao = address!("af4ba5015Eb5bE8780e664e2BE40144668361B0f");
+        let cases: [(u8, &[u64], &[u8], u64, B256); 3] = [
+            (
+                2,
+                &[],
+                &[],
+                5,
+                b256!("7b2469e02219a65074e2c84fcb48cacb391b995cd2c83b45920d46a710d85f67"),
+            ),
+            (
+                0,
+                &[1, 2, 3],
+                &[],
+                6,
+                b256!("ff700e1470fbc5a59e4a548f736284e2a4d2de1cd98816e7eadd6afdc15784bd"),
+            ),
+            (
+                0,
+                &[],
+                &[2, 1],
+                5,
+                b256!("0ee36c8c640bb92d0199542a9e87a4a0389b5d190dc3af8ce148c46a5c0b1815"),
+            ),
+        ];
+        for (direction, amounts, choices, balance, guest_digest) in cases {
+            let amounts: Vec<U256> = amounts.iter().map(|amount| U256::from(*amount)).collect();
+            let message = vote_message(
+                11155111,
+                dao,
+                U256::from(1),
+                direction,
+                &amounts,
+                choices,
+                U256::from(balance),
+            );
+            // The guest prefixes the message like an EIP-191 personal message of 32 bytes,
+            // which is what `recover_signer` expects to be signed.
+            let prefixed = [
+                b"\x19Ethereum Signed Message:\n32".as_slice(),
+                message.as_slice(),
+            ];
+            assert_eq!(keccak256(prefixed.concat()), guest_digest);
+            assert_eq!(eip191_hash_message(message), guest_digest);
+        }
+    }
+
+    #[test]
+    fn test_check_request() {
+        assert!(check_request(&request(2, &[], &[])).is_ok());
+        assert!(check_request(&request(0, &[1, 2, 3], &[])).is_ok());
+        assert!(check_request(&request(0, &[], &[2, 1])).is_ok());
+
+        // Out of range directions.
+        assert!(check_request(&request(4, &[], &[])).is_err());
+        // Split votes need exactly three amounts, and only split votes take amounts.
+        assert!(check_request(&request(0, &[], &[])).is_err());
+        assert!(check_request(&request(0, &[1, 2], &[])).is_err());
+        assert!(check_request(&request(2, &[1, 2, 3], &[])).is_err());
+        // Ballots take direction 0, no amounts and distinct options numbered from 1.
+        assert!(check_request(&request(2, &[], &[1])).is_err());
+        assert!(check_request(&request(0, &[1, 2, 3], &[1])).is_err());
+        assert!(check_request(&request(0, &[], &[0])).is_err());
+        assert!(check_request(&request(0, &[], &[1, 1])).is_err());
+        let too_many: Vec<u8> = (1..=MAX_BALLOT_CHOICES as u8 + 1).collect();
+        assert!(check_request(&request(0, &[], &too_many)).is_err());
+    }
+
+    #[test]
+    fn test_check_amounts() {
+        let balance = U256::from(6);
+        assert!(check_amounts(&request(0, &[1, 2, 3], &[]), balance).is_ok());
+        assert!(check_amounts(&request(0, &[1, 2, 2], &[]), balance).is_err());
+        assert!(check_amounts(&request(0, &[1, 2, 4], &[]), balance).is_err());
+        // Overflowing amounts never add up.
+        let overflowing = VoteRequest {
+            amounts: vec![U256::MAX, U256::from(7), U256::ZERO],
+            ..request(0, &[], &[])
+        };
+        assert!(check_amounts(&overflowing, balance).is_err());
+        // Only split votes carry amounts to check.
+        assert!(check_amounts(&request(2, &[], &[]), balance).is_ok());
+        assert!(check_amounts(&request(0, &[], &[2, 1]), balance).is_ok());
+    }
+}
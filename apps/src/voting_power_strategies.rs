@@ -1,6 +1,7 @@
 use crate::{Asset, EthHostEvmEnv};
 use alloy::{network::Network, providers::Provider, transports::Transport};
 use alloy_primitives::{Address, U256};
+use anyhow::Result;
 use async_trait::async_trait;
 
 #[async_trait]
@@ -16,12 +17,18 @@ where
         env: &mut EthHostEvmEnv<T, N, P, H>,
         account: Address,
         asset: &Asset,
-    ) -> U256;
+    ) -> Result<U256>;
 
-    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> U256;
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256>;
 }
 
 mod balance_of;
 mod get_past_votes;
+mod membership;
+mod merkle_census;
+mod min_balance_of;
 pub use balance_of::BalanceOf;
 pub use get_past_votes::GetPastVotes;
+pub use membership::Membership;
+pub use merkle_census::MerkleCensus;
+pub use min_balance_of::{LookbackEnvs, MinBalanceOf};
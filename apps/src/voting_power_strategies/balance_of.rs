@@ -3,6 +3,7 @@ use crate::{Asset, EthHostEvmEnv};
 use alloy::{network::Network, providers::Provider, transports::Transport};
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::sol;
+use anyhow::Result;
 use async_trait::async_trait;
 use risc0_steel::Contract;
 
@@ -29,26 +30,18 @@ where
         env: &mut EthHostEvmEnv<T, N, P, H>,
         account: Address,
         asset: &Asset,
-    ) -> U256 {
+    ) -> Result<U256> {
         let mut asset_contract = Contract::preflight(asset.contract, env);
         let balance_call = IERC20::balanceOfCall { account };
-        let balance = asset_contract
-            .call_builder(&balance_call)
-            .call()
-            .await
-            .unwrap();
-        U256::from(balance._0)
+        let balance = asset_contract.call_builder(&balance_call).call().await?;
+        Ok(U256::from(balance._0))
     }
 
-    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> U256 {
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256> {
         let mut asset_contract = Contract::preflight(asset.contract, env);
         let supply_call = IERC20::getTotalSupplyCall {};
-        let supply = asset_contract
-            .call_builder(&supply_call)
-            .call()
-            .await
-            .unwrap();
-        U256::from(supply._0)
+        let supply = asset_contract.call_builder(&supply_call).call().await?;
+        Ok(U256::from(supply._0))
     }
 }
 
@@ -61,6 +54,7 @@ mod tests {
     use alloy::transports::http::reqwest::Url;
     use alloy_primitives::address;
     use risc0_steel::ethereum::{EthEvmEnv, ETH_SEPOLIA_CHAIN_SPEC};
+    use strategies::voting_strategies::MEMBER_VOTING_POWER;
 
     use crate::DelegationObject;
 
@@ -71,8 +65,7 @@ mod tests {
         let mut env = EthEvmEnv::builder()
             .rpc(Url::from_str(&std::env::var("RPC_URL").unwrap()).unwrap())
             .build()
-            .await
-            .unwrap();
+            .await?;
         env = env.with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
 
         let account = address!("8bF1e340055c7dE62F11229A149d3A1918de3d74");
@@ -84,9 +77,13 @@ mod tests {
                 contract: address!("185Bb1cca668C474214e934028A3e4BB7A5E6525"),
                 strategy: "SplitDelegation".to_string(),
             },
+            lookback_blocks: Vec::new(),
+            member_call: Default::default(),
+            member_count_call: Default::default(),
+            member_voting_power: MEMBER_VOTING_POWER,
         };
         let balance_strategy = BalanceOf;
-        let balance = balance_strategy.process(&mut env, account, &asset).await;
+        let balance = balance_strategy.process(&mut env, account, &asset).await?;
         assert_eq!(balance, U256::from_str("900000000000000000").unwrap());
         Ok(())
     }
@@ -3,6 +3,7 @@ use crate::{Asset, EthHostEvmEnv};
 use alloy::{network::Network, providers::Provider, transports::Transport};
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::sol;
+use anyhow::Result;
 use async_trait::async_trait;
 use risc0_steel::Contract;
 
@@ -30,33 +31,25 @@ where
         env: &mut EthHostEvmEnv<T, N, P, H>,
         account: Address,
         asset: &Asset,
-    ) -> U256 {
+    ) -> Result<U256> {
         let block_number = env.header().parent_num_hash().number;
         let mut asset_contract = Contract::preflight(asset.contract, env);
         let past_votes_call = IERC20Votes::getPastVotesCall {
             account,
             timepoint: U256::from(block_number),
         };
-        let past_votes = asset_contract
-            .call_builder(&past_votes_call)
-            .call()
-            .await
-            .unwrap();
-        U256::from(past_votes._0)
+        let past_votes = asset_contract.call_builder(&past_votes_call).call().await?;
+        Ok(U256::from(past_votes._0))
     }
 
-    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> U256 {
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256> {
         let block_number = env.header().parent_num_hash().number;
         let mut asset_contract = Contract::preflight(asset.contract, env);
         let supply_call = IERC20Votes::getPastTotalSupplyCall {
             timepoint: U256::from(block_number),
         };
-        let supply = asset_contract
-            .call_builder(&supply_call)
-            .call()
-            .await
-            .unwrap();
-        U256::from(supply._0)
+        let supply = asset_contract.call_builder(&supply_call).call().await?;
+        Ok(U256::from(supply._0))
     }
 }
 
@@ -69,6 +62,7 @@ mod tests {
     use alloy::transports::http::reqwest::Url;
     use alloy_primitives::address;
     use risc0_steel::ethereum::{EthEvmEnv, ETH_SEPOLIA_CHAIN_SPEC};
+    use strategies::voting_strategies::MEMBER_VOTING_POWER;
 
     use crate::DelegationObject;
 
@@ -79,8 +73,7 @@ mod tests {
         let mut env = EthEvmEnv::builder()
             .rpc(Url::from_str(&std::env::var("RPC_URL").unwrap()).unwrap())
             .build()
-            .await
-            .unwrap();
+            .await?;
         env = env.with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
 
         let account = address!("8bF1e340055c7dE62F11229A149d3A1918de3d74");
@@ -92,9 +85,15 @@ mod tests {
                 contract: address!("185Bb1cca668C474214e934028A3e4BB7A5E6525"),
                 strategy: "SplitDelegation".to_string(),
             },
+            lookback_blocks: Vec::new(),
+            member_call: Default::default(),
+            member_count_call: Default::default(),
+            member_voting_power: MEMBER_VOTING_POWER,
         };
         let past_votes_strategy = GetPastVotes;
-        let past_votes = past_votes_strategy.process(&mut env, account, &asset).await;
+        let past_votes = past_votes_strategy
+            .process(&mut env, account, &asset)
+            .await?;
         assert_eq!(past_votes, U256::from_str("900000000000000000").unwrap());
         Ok(())
     }
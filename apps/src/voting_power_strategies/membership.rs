@@ -0,0 +1,84 @@
+use super::VotingPowerStrategy;
+use crate::{Asset, EthHostEvmEnv};
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use risc0_steel::Contract;
+use strategies::voting_strategies::{membership_supply, MemberCall, MemberCountCall};
+
+sol! {
+    /// Aragon `IMembership` function signature.
+    interface IMembership {
+        function isMember(address account) external view returns (bool);
+    }
+
+    /// Aragon `Addresslist` function signatures.
+    interface IAddresslist {
+        function isListed(address account) external view returns (bool);
+        function addresslistLength() external view returns (uint256);
+    }
+
+    /// Safe `OwnerManager` function signatures.
+    interface IOwnerManager {
+        function isOwner(address owner) external view returns (bool);
+        function getOwners() external view returns (address[] memory);
+    }
+}
+
+pub struct Membership;
+
+#[async_trait]
+impl<T, N, P, H> VotingPowerStrategy<T, N, P, H> for Membership
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn process(
+        &self,
+        env: &mut EthHostEvmEnv<T, N, P, H>,
+        account: Address,
+        asset: &Asset,
+    ) -> Result<U256> {
+        let mut membership_contract = Contract::preflight(asset.contract, env);
+        let is_member = match asset.member_call {
+            MemberCall::IsMember => {
+                let call = IMembership::isMemberCall { account };
+                membership_contract.call_builder(&call).call().await?._0
+            }
+            MemberCall::IsListed => {
+                let call = IAddresslist::isListedCall { account };
+                membership_contract.call_builder(&call).call().await?._0
+            }
+            MemberCall::IsOwner => {
+                let call = IOwnerManager::isOwnerCall { owner: account };
+                membership_contract.call_builder(&call).call().await?._0
+            }
+        };
+        if is_member {
+            Ok(asset.member_voting_power)
+        } else {
+            Ok(U256::ZERO)
+        }
+    }
+
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256> {
+        let mut membership_contract = Contract::preflight(asset.contract, env);
+        let member_count = match asset.member_count_call {
+            MemberCountCall::AddresslistLength => {
+                let call = IAddresslist::addresslistLengthCall {};
+                membership_contract.call_builder(&call).call().await?._0
+            }
+            MemberCountCall::GetOwners => {
+                let call = IOwnerManager::getOwnersCall {};
+                let owners = membership_contract.call_builder(&call).call().await?;
+                U256::from(owners._0.len())
+            }
+        };
+        membership_supply(member_count, asset.member_voting_power)
+            .context("membership supply overflows")
+    }
+}
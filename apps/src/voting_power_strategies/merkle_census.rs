@@ -0,0 +1,81 @@
+use super::VotingPowerStrategy;
+use crate::{Asset, EthHostEvmEnv};
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use risc0_steel::Contract;
+use std::collections::HashMap;
+use strategies::voting_strategies::{census_leaf, verify_census_proof, CensusProof};
+
+sol! {
+    /// Merkle census function signatures.
+    interface IMerkleCensus {
+        function censusRoot() external view returns (bytes32);
+        function totalWeight() external view returns (uint256);
+    }
+}
+
+#[derive(Default)]
+pub struct MerkleCensus {
+    proofs: HashMap<(Address, Address), CensusProof>,
+}
+
+impl MerkleCensus {
+    pub fn new(proofs: Vec<CensusProof>) -> Self {
+        Self {
+            proofs: proofs
+                .into_iter()
+                .map(|proof| ((proof.asset, proof.account), proof))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> VotingPowerStrategy<T, N, P, H> for MerkleCensus
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn process(
+        &self,
+        env: &mut EthHostEvmEnv<T, N, P, H>,
+        account: Address,
+        asset: &Asset,
+    ) -> Result<U256> {
+        // Accounts without a leaf are not part of the census.
+        let Some(census_proof) = self.proofs.get(&(asset.contract, account)) else {
+            return Ok(U256::ZERO);
+        };
+
+        let mut census_contract = Contract::preflight(asset.contract, env);
+        let root_call = IMerkleCensus::censusRootCall {};
+        let root = census_contract.call_builder(&root_call).call().await?;
+
+        ensure!(
+            verify_census_proof(
+                root._0,
+                census_leaf(account, census_proof.weight),
+                &census_proof.proof
+            ),
+            "invalid census proof for {} in census {}",
+            account,
+            asset.contract
+        );
+        Ok(census_proof.weight)
+    }
+
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256> {
+        let mut census_contract = Contract::preflight(asset.contract, env);
+        let total_weight_call = IMerkleCensus::totalWeightCall {};
+        let total_weight = census_contract
+            .call_builder(&total_weight_call)
+            .call()
+            .await?;
+        Ok(total_weight._0)
+    }
+}
@@ -0,0 +1,80 @@
+use super::{BalanceOf, VotingPowerStrategy};
+use crate::{Asset, EthHostEvmEnv};
+use alloy::{network::Network, providers::Provider, transports::Transport};
+use alloy_primitives::{Address, U256};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use strategies::voting_strategies::lookback_block;
+use tokio::sync::Mutex;
+
+/// Look-back environments shared with the caller, which turns them into guest inputs once
+/// the preflight is done. They always use block commitments, since the guest links them to the
+/// snapshot through their block hashes.
+pub type LookbackEnvs<T, N, P> = Arc<Mutex<Vec<EthHostEvmEnv<T, N, P, ()>>>>;
+
+pub struct MinBalanceOf<T, N, P>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+{
+    lookback_envs: LookbackEnvs<T, N, P>,
+}
+
+impl<T, N, P> MinBalanceOf<T, N, P>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+{
+    pub fn new(lookback_envs: LookbackEnvs<T, N, P>) -> Self {
+        Self { lookback_envs }
+    }
+}
+
+impl<T, N, P> Default for MinBalanceOf<T, N, P>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new(Arc::new(Mutex::new(Vec::new())))
+    }
+}
+
+#[async_trait]
+impl<T, N, P, H> VotingPowerStrategy<T, N, P, H> for MinBalanceOf<T, N, P>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + 'static,
+    H: Send + 'static,
+{
+    async fn process(
+        &self,
+        env: &mut EthHostEvmEnv<T, N, P, H>,
+        account: Address,
+        asset: &Asset,
+    ) -> Result<U256> {
+        let snapshot = env.header().number;
+        let mut balance = BalanceOf.process(env, account, asset).await?;
+
+        let mut lookback_envs = self.lookback_envs.lock().await;
+        for offset in &asset.lookback_blocks {
+            let number = lookback_block(snapshot, *offset)
+                .with_context(|| format!("invalid look-back offset: {}", offset))?;
+            let lookback_env = lookback_envs
+                .iter_mut()
+                .find(|lookback_env| lookback_env.header().number == number)
+                .with_context(|| format!("look-back block not provided: {}", number))?;
+            balance = balance.min(BalanceOf.process(lookback_env, account, asset).await?);
+        }
+        Ok(balance)
+    }
+
+    async fn get_supply(&self, env: &mut EthHostEvmEnv<T, N, P, H>, asset: &Asset) -> Result<U256> {
+        BalanceOf.get_supply(env, asset).await
+    }
+}
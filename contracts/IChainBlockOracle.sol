@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+pragma solidity ^0.8.17;
+
+/// @title IChainBlockOracle
+/// @notice Anchors the blocks of another chain on this one, such as an adapter over a rollup's output oracle.
+/// @dev The plugin trusts the oracle of each chain to only report blocks of that chain.
+interface IChainBlockOracle {
+    /// @notice Returns the latest block of the chain known on this chain.
+    /// @return blockNumber The number of the block.
+    /// @return blockHash The hash of the block.
+    function latestBlock()
+        external
+        view
+        returns (uint64 blockNumber, bytes32 blockHash);
+}
@@ -151,6 +151,7 @@ abstract contract MajorityVotingBase is
     /// Its value has to be in the interval [0, 10^6] defined by `RATIO_BASE = 10**6`.
     /// @param minDuration The minimum duration of the proposal vote in seconds.
     /// @param minProposerVotingPower The minimum voting power required to create a proposal.
+    /// @param proposalProtocolImageId The image ID of the guest proving that a proposer holds `minProposerVotingPower`.
     struct VotingSettings {
         VotingMode votingMode;
         uint32 supportThreshold;
@@ -161,6 +162,7 @@ abstract contract MajorityVotingBase is
         address verifier;
         bytes32 votingProtocolImageId;
         bytes32 executionProtocolImageId;
+        bytes32 proposalProtocolImageId;
     }
 
     /// @notice A container for proposal-related information.
@@ -168,10 +170,17 @@ abstract contract MajorityVotingBase is
     /// @param parameters The proposal parameters at the time of the proposal creation.
     /// @param tally The vote tally of the proposal.
     /// @param voters The votes casted by the voters.
-    /// @param actions The actions to be executed when the proposal passes.
+    /// @param actions The actions to be executed when the proposal passes. For a multi-option proposal, the actions
+    /// of the winning option, copied from `optionActions` on execution.
     /// @param allowFailureMap A bitmap allowing the proposal to succeed, even if individual actions might revert.
     /// If the bit at index `i` is 1, the proposal succeeds even if the `i`th action reverts.
     /// A failure map value of 0 requires every action to not revert.
+    /// @param voterTallies The voting power each voter gave to every option, which also covers split votes.
+    /// @param ballots The ballots of a multi-option proposal, as pairs of voting power and choices packed one byte
+    /// per option, the most preferred in the lowest byte.
+    /// @param ballotNumbers The position of each voter's ballot in `ballots`, counted from 1.
+    /// @param winningOption The option chosen by the execution proof of a multi-option proposal, numbered from 1.
+    /// @param optionActions The actions of each option of a multi-option proposal, run when the option wins.
     struct Proposal {
         bool executed;
         ProposalParameters parameters;
@@ -179,6 +188,11 @@ abstract contract MajorityVotingBase is
         mapping(address => IMajorityVoting.VoteOption) voters;
         IDAO.Action[] actions;
         uint256 allowFailureMap;
+        mapping(address => Tally) voterTallies;
+        uint256[] ballots;
+        mapping(address => uint256) ballotNumbers;
+        uint8 winningOption;
+        mapping(uint8 => IDAO.Action[]) optionActions;
     }
 
     /// @notice A container for the proposal parameters at the time of proposal creation.
@@ -188,6 +202,10 @@ abstract contract MajorityVotingBase is
     /// @param startDate The start date of the proposal vote.
     /// @param endDate The end date of the proposal vote.
     /// @param snapshotBlock The number of the block prior to the proposal creation.
+    /// @param snapshotBeaconTimestamp The timestamp of the proposal creation block, under which EIP-4788 stores the beacon block root of the snapshot block.
+    /// @param snapshotBeaconRoot The beacon block root of the snapshot block, if EIP-4788 is available.
+    /// @param votingProtocolConfigHash The keccak256 hash of `votingProtocolConfig`.
+    /// @param optionCount The number of options of a multi-option proposal, or 0 for a yes/no/abstain proposal.
     struct ProposalParameters {
         VotingMode votingMode;
         uint32 supportThreshold;
@@ -199,6 +217,10 @@ abstract contract MajorityVotingBase is
         address verifier;
         bytes32 votingProtocolImageId;
         bytes32 executionProtocolImageId;
+        uint64 snapshotBeaconTimestamp;
+        bytes32 snapshotBeaconRoot;
+        bytes32 votingProtocolConfigHash;
+        uint8 optionCount;
     }
 
     /// @notice A container for the proposal vote tally.
@@ -235,6 +257,9 @@ abstract contract MajorityVotingBase is
     /// @notice The struct storing the voting settings.
     VotingSettings private votingSettings;
 
+    /// @notice The keccak256 hash of `votingSettings.votingProtocolConfig`, checked by the guests.
+    bytes32 private currentVotingProtocolConfigHash;
+
     /// @notice Thrown if a date is out of bounds.
     /// @param limit The limit value.
     /// @param actual The actual value.
@@ -281,6 +306,23 @@ abstract contract MajorityVotingBase is
         uint256 minProposerVotingPower
     );
 
+    /// @notice Emitted when a ballot is cast on a multi-option proposal.
+    /// @param proposalId The ID of the proposal.
+    /// @param voter The voter casting the ballot.
+    /// @param choices The chosen options, numbered from 1, most preferred first.
+    /// @param votingPower The voting power behind this ballot.
+    event BallotCast(
+        uint256 indexed proposalId,
+        address indexed voter,
+        uint8[] choices,
+        uint256 votingPower
+    );
+
+    /// @notice Emitted when the execution of a multi-option proposal records its winning option.
+    /// @param proposalId The ID of the proposal.
+    /// @param winningOption The winning option, numbered from 1.
+    event WinningOptionChosen(uint256 indexed proposalId, uint8 winningOption);
+
     /// @notice Initializes the component to be used by inheriting contracts.
     /// @dev This method is required to support [ERC-1822](https://eips.ethereum.org/EIPS/eip-1822).
     /// @param _dao The IDAO interface of the associated DAO.
@@ -326,6 +368,47 @@ abstract contract MajorityVotingBase is
         return proposals[_proposalId].voters[_voter];
     }
 
+    /// @notice Returns the voting power a voter gave to every option of a proposal.
+    /// @dev Split votes leave `getVoteOption` at `None`, so this is the way to read them.
+    /// @param _proposalId The ID of the proposal.
+    /// @param _voter The address of the voter.
+    /// @return The voting power given to abstain, yes and no.
+    function getVoterTally(
+        uint256 _proposalId,
+        address _voter
+    ) public view virtual returns (Tally memory) {
+        return proposals[_proposalId].voterTallies[_voter];
+    }
+
+    /// @notice Returns the ballots of a multi-option proposal, the tally its execution proof is checked against.
+    /// @param _proposalId The ID of the proposal.
+    /// @return Pairs of voting power and packed choices, one per voter.
+    function getBallots(
+        uint256 _proposalId
+    ) public view virtual returns (uint256[] memory) {
+        return proposals[_proposalId].ballots;
+    }
+
+    /// @notice Returns the winning option of an executed multi-option proposal.
+    /// @param _proposalId The ID of the proposal.
+    /// @return The winning option, numbered from 1, or 0 if there is none yet.
+    function getWinningOption(
+        uint256 _proposalId
+    ) public view virtual returns (uint8) {
+        return proposals[_proposalId].winningOption;
+    }
+
+    /// @notice Returns the actions run when an option of a multi-option proposal wins.
+    /// @param _proposalId The ID of the proposal.
+    /// @param _option The option, numbered from 1.
+    /// @return The actions of the option.
+    function getOptionActions(
+        uint256 _proposalId,
+        uint8 _option
+    ) public view virtual returns (IDAO.Action[] memory) {
+        return proposals[_proposalId].optionActions[_option];
+    }
+
     /// @inheritdoc IMajorityVoting
     function canVote(
         uint256 _proposalId,
@@ -394,6 +477,12 @@ abstract contract MajorityVotingBase is
         return votingSettings.executionProtocolImageId;
     }
 
+    /// @notice Returns the proposal protocol image ID stored in the voting settings.
+    /// @return The proposal protocol image ID.
+    function proposalProtocolImageId() public view virtual returns (bytes32) {
+        return votingSettings.proposalProtocolImageId;
+    }
+
     /// @notice getter function for config of the voting protocol.
     /// @return The string of the config.
     function votingProtocolConfig() public view returns (string memory) {
@@ -410,6 +499,22 @@ abstract contract MajorityVotingBase is
         return proposal_.parameters.votingProtocolConfig;
     }
 
+    /// @notice getter function for the hash of the config of the voting protocol.
+    /// @return The hash of the config.
+    function votingProtocolConfigHash() public view returns (bytes32) {
+        return currentVotingProtocolConfigHash;
+    }
+
+    /// @notice getter function for the hash of the config of a specific proposal.
+    /// @param _proposalId The ID of the proposal.
+    /// @return The hash of the config.
+    function votingProtocolConfigHash(
+        uint256 _proposalId
+    ) public view returns (bytes32) {
+        Proposal storage proposal_ = proposals[_proposalId];
+        return proposal_.parameters.votingProtocolConfigHash;
+    }
+
     /// @notice Returns all information for a proposal vote by its ID.
     /// @param _proposalId The ID of the proposal.
     /// @return open Whether the proposal is open or not.
@@ -471,6 +576,48 @@ abstract contract MajorityVotingBase is
         uint64 _endDate
     ) external virtual returns (uint256 proposalId);
 
+    /// @notice Creates a new multi-option proposal, voted on with approval or ranked ballots.
+    /// @dev The config's multi-option execution strategy picks the winning option from the ballots, and only the
+    /// actions of that option run once it is recorded.
+    /// @param _metadata The metadata of the proposal.
+    /// @param _actions The actions of all options.
+    /// @param _actionOptions The option each action belongs to, numbered from 1, in the order of `_actions`.
+    /// @param _allowFailureMap Allows proposal to succeed even if an action reverts. Bit `i` refers to the `i`th
+    /// action of the winning option.
+    /// @param _startDate The start date of the proposal vote.
+    /// @param _endDate The end date of the proposal vote.
+    /// @param _optionCount The number of options, between 2 and 255.
+    /// @return proposalId The ID of the proposal.
+    function createMultiOptionProposal(
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint8[] calldata _actionOptions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate,
+        uint8 _optionCount
+    ) external virtual returns (uint256 proposalId);
+
+    /// @notice Creates a new majority voting proposal on behalf of a proposer proving `minProposerVotingPower`.
+    /// @dev The journal commits to the hash of the proposal's parameters, and each proof creates a single proposal.
+    /// @param journalData The journal of the proposal protocol guest, containing the proposer and its voting power.
+    /// @param seal The seal of the journal data.
+    /// @param _metadata The metadata of the proposal.
+    /// @param _actions The actions that will be executed after the proposal passes.
+    /// @param _allowFailureMap Allows proposal to succeed even if an action reverts.
+    /// @param _startDate The start date of the proposal vote.
+    /// @param _endDate The end date of the proposal vote.
+    /// @return proposalId The ID of the proposal.
+    function createProposalWithProof(
+        bytes calldata journalData,
+        bytes calldata seal,
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate
+    ) external virtual returns (uint256 proposalId);
+
     /// @notice Internal function to execute a vote. It assumes the queried proposal exists.
     /// @param _proposalId The ID of the proposal.
     function _execute(uint256 _proposalId) internal virtual {
@@ -571,6 +718,9 @@ abstract contract MajorityVotingBase is
         }
 
         votingSettings = _votingSettings;
+        currentVotingProtocolConfigHash = keccak256(
+            bytes(_votingSettings.votingProtocolConfig)
+        );
 
         emit VotingSettingsUpdated({
             votingMode: _votingSettings.votingMode,
@@ -628,5 +778,5 @@ abstract contract MajorityVotingBase is
     /// new variables without shifting down storage in the inheritance chain
     /// (see [OpenZeppelin's guide about storage gaps]
     /// (https://docs.openzeppelin.com/contracts/4.x/upgradeable#storage_gaps)).
-    uint256[47] private __gap;
+    uint256[45] private __gap;
 }
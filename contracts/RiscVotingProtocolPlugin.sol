@@ -11,8 +11,10 @@ import {IMembership} from "@aragon/osx/core/plugin/membership/IMembership.sol";
 import {IDAO} from "@aragon/osx/core/dao/IDAO.sol";
 
 import {IRiscZeroVerifier} from "risc0/IRiscZeroVerifier.sol";
-import {Steel, Encoding} from "risc0/steel/Steel.sol";
+import {Steel, Beacon, Encoding} from "risc0/steel/Steel.sol";
 import {ImageID} from "./ImageID.sol"; // auto-generated contract after running `cargo build`.
+import {Journal} from "./Journal.sol"; // auto-generated from the `journal` crate.
+import {IChainBlockOracle} from "./IChainBlockOracle.sol";
 
 /// @title Counter
 /// @notice Implements a counter that increments based on off-chain Steel proofs submitted to this contract.
@@ -21,28 +23,38 @@ import {ImageID} from "./ImageID.sol"; // auto-generated contract after running
 contract RiscVotingProtocolPlugin is MajorityVotingBase {
     using SafeCastUpgradeable for uint256;
 
-    /// @notice Journal that is committed to by the guest.
-    struct VotingJournal {
-        Steel.Commitment commitment;
-        address configContract;
-        uint256 proposalId;
-        address voter;
-        uint256 balance;
-        uint8 direction;
-    }
+    /// @notice Counter to track the number of successful verifications.
+    uint256 public counter;
 
-    // TODO: Journal should be in sync with guest
-    struct ExecutionJournal {
-        Steel.Commitment commitment;
-        address configContract;
-        uint256 proposalId;
-        address voter;
-        uint256 balance;
-        uint8 direction;
+    /// @notice A block of another chain holding assets of the census.
+    /// @param chainId The chain ID.
+    /// @param blockNumber The number of the block.
+    /// @param blockHash The hash of the block.
+    struct ChainBlock {
+        uint64 chainId;
+        uint64 blockNumber;
+        bytes32 blockHash;
     }
 
-    /// @notice Counter to track the number of successful verifications.
-    uint256 public counter;
+    /// @notice The other chains holding assets of the census, in ascending order.
+    uint64[] internal chainIds;
+
+    /// @notice The oracle anchoring the blocks of each chain in `chainIds`.
+    mapping(uint64 => IChainBlockOracle) internal chainOracles;
+
+    /// @notice The blocks of the other chains each proposal is evaluated at, pinned at its creation.
+    mapping(uint256 => ChainBlock[]) internal proposalChainBlocks;
+
+    /// @notice Whether a proposal protocol journal, by its SHA-256 digest, already created a proposal.
+    mapping(bytes32 => bool) internal usedProposalProofs;
+
+    /// @notice The maximum number of voters casting a ballot on a multi-option proposal.
+    uint256 public constant MAX_BALLOTS = 1000;
+
+    /// @notice Emitted when the oracle of another chain is set or removed.
+    /// @param chainId The chain ID.
+    /// @param oracle The oracle, or zero when the chain was removed.
+    event ChainOracleUpdated(uint64 indexed chainId, IChainBlockOracle oracle);
 
     function initialize(
         IDAO _dao,
@@ -53,6 +65,53 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
         __MajorityVotingBase_init(_dao, _votingSettings);
     }
 
+    /// @notice Sets the oracle anchoring the blocks of another chain holding assets of the census.
+    /// @dev Proposals created afterwards are evaluated at the oracle's latest block of that chain, and their proofs
+    /// must commit to it. Pass a zero oracle to remove the chain.
+    /// @param _chainId The chain ID.
+    /// @param _oracle The oracle of the chain.
+    function setChainOracle(
+        uint64 _chainId,
+        IChainBlockOracle _oracle
+    ) external auth(UPDATE_VOTING_SETTINGS_PERMISSION_ID) {
+        _setChainOracle(_chainId, _oracle);
+    }
+
+    /// @notice Returns the oracle anchoring the blocks of another chain, or zero.
+    /// @param _chainId The chain ID.
+    function chainOracle(
+        uint64 _chainId
+    ) external view returns (IChainBlockOracle) {
+        return chainOracles[_chainId];
+    }
+
+    /// @notice Returns the blocks of the other chains a new proposal would be evaluated at.
+    /// @return blocks The latest block of every chain with an oracle, in ascending chain ID order.
+    function currentChainBlocks()
+        public
+        view
+        returns (ChainBlock[] memory blocks)
+    {
+        blocks = new ChainBlock[](chainIds.length);
+        for (uint256 i; i < chainIds.length; ) {
+            (uint64 blockNumber, bytes32 blockHash) = chainOracles[chainIds[i]]
+                .latestBlock();
+            require(blockHash != bytes32(0), "Invalid chain block");
+            blocks[i] = ChainBlock(chainIds[i], blockNumber, blockHash);
+            unchecked {
+                ++i;
+            }
+        }
+    }
+
+    /// @notice Returns the blocks of the other chains a proposal is evaluated at.
+    /// @param _proposalId The ID of the proposal.
+    function getChainBlocks(
+        uint256 _proposalId
+    ) external view returns (ChainBlock[] memory) {
+        return proposalChainBlocks[_proposalId];
+    }
+
     /// @inheritdoc MajorityVotingBase
     function createProposal(
         bytes calldata _metadata,
@@ -66,6 +125,183 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
         auth(CREATE_PROPOSAL_PERMISSION_ID)
         returns (uint256 proposalId)
     {
+        proposalId = _createRiscProposal(
+            _metadata,
+            _actions,
+            _allowFailureMap,
+            _startDate,
+            _endDate,
+            0
+        );
+    }
+
+    /// @inheritdoc MajorityVotingBase
+    function createMultiOptionProposal(
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint8[] calldata _actionOptions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate,
+        uint8 _optionCount
+    )
+        external
+        override
+        auth(CREATE_PROPOSAL_PERMISSION_ID)
+        returns (uint256 proposalId)
+    {
+        proposalId = _createMultiOptionProposal(
+            _metadata,
+            _actions,
+            _actionOptions,
+            _allowFailureMap,
+            _startDate,
+            _endDate,
+            _optionCount
+        );
+    }
+
+    /// @notice Creates a multi-option proposal and stores the actions of each option.
+    /// @dev `ProposalCreated` lists the actions of all options; the plugin only runs those of the winning option.
+    function _createMultiOptionProposal(
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint8[] calldata _actionOptions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate,
+        uint8 _optionCount
+    ) internal returns (uint256 proposalId) {
+        require(_optionCount >= 2, "Invalid option count");
+        require(
+            _actionOptions.length == _actions.length,
+            "Invalid action options"
+        );
+        proposalId = _createRiscProposal(
+            _metadata,
+            _actions,
+            _allowFailureMap,
+            _startDate,
+            _endDate,
+            _optionCount
+        );
+
+        Proposal storage proposal_ = proposals[proposalId];
+        for (uint256 i; i < _actions.length; ) {
+            uint8 option = _actionOptions[i];
+            require(
+                option != 0 && option <= _optionCount,
+                "Invalid action options"
+            );
+            proposal_.optionActions[option].push(_actions[i]);
+            unchecked {
+                ++i;
+            }
+        }
+    }
+
+    /// @notice Returns the hash of a proposal's parameters, which the proposal protocol journal commits to.
+    /// @param _metadata The metadata of the proposal.
+    /// @param _actions The actions that will be executed after the proposal passes.
+    /// @param _allowFailureMap Allows proposal to succeed even if an action reverts.
+    /// @param _startDate The start date of the proposal vote.
+    /// @param _endDate The end date of the proposal vote.
+    function proposalParamsHash(
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate
+    ) public pure returns (bytes32) {
+        return
+            keccak256(
+                abi.encode(
+                    _metadata,
+                    _actions,
+                    _allowFailureMap,
+                    _startDate,
+                    _endDate
+                )
+            );
+    }
+
+    /// @inheritdoc MajorityVotingBase
+    function createProposalWithProof(
+        bytes calldata journalData,
+        bytes calldata seal,
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate
+    ) external override returns (uint256 proposalId) {
+        // Decode and validate the journal data
+        Journal.ProposalJournal memory journal = abi.decode(
+            journalData,
+            (Journal.ProposalJournal)
+        );
+        require(
+            journal.configContract == address(this),
+            "Invalid token address"
+        );
+        require(journal.proposer == _msgSender(), "Invalid proposer");
+        require(
+            journal.paramsHash ==
+                proposalParamsHash(
+                    _metadata,
+                    _actions,
+                    _allowFailureMap,
+                    _startDate,
+                    _endDate
+                ),
+            "Invalid proposal parameters"
+        );
+        require(
+            journal.configHash == votingProtocolConfigHash(),
+            "Invalid config hash"
+        );
+        require(
+            journal.votingPower >= minProposerVotingPower(),
+            "Insufficient voting power"
+        );
+        require(
+            Steel.validateCommitment(journal.commitment),
+            "Invalid commitment"
+        );
+
+        // Verify the proof, which can only create one proposal.
+        bytes32 journalHash = sha256(journalData);
+        require(!usedProposalProofs[journalHash], "Proof already used");
+        usedProposalProofs[journalHash] = true;
+        IRiscZeroVerifier(verifier()).verify(
+            seal,
+            proposalProtocolImageId(),
+            journalHash
+        );
+
+        proposalId = _createRiscProposal(
+            _metadata,
+            _actions,
+            _allowFailureMap,
+            _startDate,
+            _endDate,
+            0
+        );
+
+        // The proposer's voting power on other chains must be proven at the blocks the proposal pinned.
+        _validateChainCommitments(journal.chainCommitments, proposalId);
+    }
+
+    /// @notice Creates a proposal and stores its parameters, once the caller is allowed to.
+    /// @dev `_optionCount` is 0 for yes/no/abstain proposals.
+    function _createRiscProposal(
+        bytes calldata _metadata,
+        IDAO.Action[] calldata _actions,
+        uint256 _allowFailureMap,
+        uint64 _startDate,
+        uint64 _endDate,
+        uint8 _optionCount
+    ) internal returns (uint256 proposalId) {
         uint256 snapshotBlock;
         unchecked {
             // The snapshot block must be mined already to
@@ -93,18 +329,36 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
         proposal_.parameters.votingMode = votingMode();
         proposal_.parameters.supportThreshold = supportThreshold();
         proposal_.parameters.snapshotBlockHash = blockhash(snapshotBlock);
+        proposal_.parameters.snapshotBeaconTimestamp = block.timestamp.toUint64();
+        proposal_.parameters.snapshotBeaconRoot = _snapshotBeaconRoot();
         proposal_.parameters.votingProtocolConfig = votingProtocolConfig();
+        proposal_
+            .parameters
+            .votingProtocolConfigHash = votingProtocolConfigHash();
         proposal_.parameters.verifier = verifier();
         proposal_.parameters.votingProtocolImageId = votingProtocolImageId();
         proposal_
             .parameters
             .executionProtocolImageId = executionProtocolImageId();
+        proposal_.parameters.optionCount = _optionCount;
+
+        ChainBlock[] memory chainBlocks = currentChainBlocks();
+        for (uint256 i; i < chainBlocks.length; ) {
+            proposalChainBlocks[proposalId].push(chainBlocks[i]);
+            unchecked {
+                ++i;
+            }
+        }
 
         // Reduce costs
         if (_allowFailureMap != 0) {
             proposal_.allowFailureMap = _allowFailureMap;
         }
 
+        // The actions of a multi-option proposal belong to its options, which store them.
+        if (_optionCount != 0) {
+            return proposalId;
+        }
         for (uint256 i; i < _actions.length; ) {
             proposal_.actions.push(_actions[i]);
             unchecked {
@@ -118,7 +372,10 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
         bytes calldata seal
     ) external override {
         // Decode and validate the journal data
-        VotingJournal memory journal = abi.decode(journalData, (VotingJournal));
+        Journal.VotingJournal memory journal = abi.decode(
+            journalData,
+            (Journal.VotingJournal)
+        );
         require(
             journal.configContract == address(this),
             "Invalid token address"
@@ -126,17 +383,15 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
 
         Proposal storage proposal_ = proposals[journal.proposalId];
 
-        require(
-            journal.commitment.digest == proposal_.parameters.snapshotBlockHash,
-            "Invalid commitment"
-        );
-
-        (uint240 claimID, uint16 _version) = Encoding.decodeVersionedID(
-            journal.commitment.id
+        _validateCommitment(
+            journal.commitment,
+            journal.chainCommitments,
+            journal.proposalId
         );
         require(
-            claimID == proposal_.parameters.snapshotBlock,
-            "Invalid commitment"
+            journal.configHash ==
+                proposal_.parameters.votingProtocolConfigHash,
+            "Invalid config hash"
         );
 
         // Verify the proof
@@ -147,41 +402,166 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
             journalHash
         );
 
+        if (proposal_.parameters.optionCount != 0) {
+            _castBallot(proposal_, journal);
+            return;
+        }
+        require(journal.choices.length == 0, "Invalid vote");
+
         // The actual vote
         // This could re-enter, though we can assume the governance token is not malicious
-        uint256 votingPower = journal.balance;
         address _voter = journal.voter;
-        VoteOption state = proposal_.voters[_voter];
-
-        // If voter had previously voted, decrease count
-        if (state == VoteOption.Yes) {
-            proposal_.tally.yes = proposal_.tally.yes - votingPower;
-        } else if (state == VoteOption.No) {
-            proposal_.tally.no = proposal_.tally.no - votingPower;
-        } else if (state == VoteOption.Abstain) {
-            proposal_.tally.abstain = proposal_.tally.abstain - votingPower;
-        }
+        Tally memory amounts = _voteAmounts(journal);
+        Tally memory previous = proposal_.voterTallies[_voter];
+
+        // Replace the voter's previous vote, if any, with the new one.
+        proposal_.tally.abstain =
+            proposal_.tally.abstain -
+            previous.abstain +
+            amounts.abstain;
+        proposal_.tally.yes = proposal_.tally.yes - previous.yes + amounts.yes;
+        proposal_.tally.no = proposal_.tally.no - previous.no + amounts.no;
 
-        // write the updated/new vote for the voter.
         VoteOption _voteOption = VoteOption(journal.direction);
-        if (_voteOption == VoteOption.Yes) {
-            proposal_.tally.yes = proposal_.tally.yes + votingPower;
-        } else if (_voteOption == VoteOption.No) {
-            proposal_.tally.no = proposal_.tally.no + votingPower;
-        } else if (_voteOption == VoteOption.Abstain) {
-            proposal_.tally.abstain = proposal_.tally.abstain + votingPower;
+        proposal_.voters[_voter] = _voteOption;
+        proposal_.voterTallies[_voter] = amounts;
+
+        if (_voteOption != VoteOption.None) {
+            emit VoteCast({
+                proposalId: journal.proposalId,
+                voter: _voter,
+                voteOption: _voteOption,
+                votingPower: journal.balance
+            });
+        } else {
+            // A split vote is reported as one vote per option it gives voting power to.
+            _emitSplitVoteCast(
+                journal.proposalId,
+                _voter,
+                VoteOption.Abstain,
+                amounts.abstain
+            );
+            _emitSplitVoteCast(
+                journal.proposalId,
+                _voter,
+                VoteOption.Yes,
+                amounts.yes
+            );
+            _emitSplitVoteCast(
+                journal.proposalId,
+                _voter,
+                VoteOption.No,
+                amounts.no
+            );
         }
+    }
 
-        proposal_.voters[_voter] = _voteOption;
+    /// @notice Records the ballot of a voter on a multi-option proposal, replacing a previous one.
+    /// @dev The choices are packed one byte per option, the most preferred in the lowest byte, and stored next to
+    /// the voting power, which is the layout of the tally the execution guest evaluates. At most `MAX_BALLOTS`
+    /// voters can cast one, so the tally stays small enough to be passed to `execute` and proven.
+    /// @param proposal_ The proposal.
+    /// @param journal The voting journal of the ballot.
+    function _castBallot(
+        Proposal storage proposal_,
+        Journal.VotingJournal memory journal
+    ) internal {
+        require(
+            journal.direction == uint8(VoteOption.None) &&
+                journal.balance != 0 &&
+                journal.amounts.length == 0 &&
+                journal.choices.length != 0 &&
+                journal.choices.length <= 32,
+            "Invalid ballot"
+        );
+
+        uint256 packedChoices;
+        uint256 chosen;
+        for (uint256 i; i < journal.choices.length; ) {
+            uint8 choice = journal.choices[i];
+            require(
+                choice != 0 &&
+                    choice <= proposal_.parameters.optionCount &&
+                    (chosen & (uint256(1) << choice)) == 0,
+                "Invalid ballot"
+            );
+            chosen |= uint256(1) << choice;
+            packedChoices |= uint256(choice) << (8 * i);
+            unchecked {
+                ++i;
+            }
+        }
 
-        emit VoteCast({
+        uint256 ballotNumber = proposal_.ballotNumbers[journal.voter];
+        if (ballotNumber == 0) {
+            require(
+                proposal_.ballots.length < 2 * MAX_BALLOTS,
+                "Too many ballots"
+            );
+            proposal_.ballots.push(journal.balance);
+            proposal_.ballots.push(packedChoices);
+            proposal_.ballotNumbers[journal.voter] = proposal_.ballots.length / 2;
+        } else {
+            proposal_.ballots[2 * (ballotNumber - 1)] = journal.balance;
+            proposal_.ballots[2 * (ballotNumber - 1) + 1] = packedChoices;
+        }
+
+        emit BallotCast({
             proposalId: journal.proposalId,
-            voter: _voter,
-            voteOption: _voteOption,
-            votingPower: votingPower
+            voter: journal.voter,
+            choices: journal.choices,
+            votingPower: journal.balance
         });
     }
 
+    /// @notice Returns the voting power a voting journal gives to every option.
+    /// @dev A split vote (direction `None`) must spread exactly the proven balance over abstain, yes and no.
+    /// @param journal The voting journal.
+    /// @return amounts The voting power given to abstain, yes and no.
+    function _voteAmounts(
+        Journal.VotingJournal memory journal
+    ) internal pure returns (Tally memory amounts) {
+        VoteOption _voteOption = VoteOption(journal.direction);
+        if (_voteOption == VoteOption.None) {
+            require(journal.amounts.length == 3, "Invalid split vote");
+            amounts = Tally({
+                abstain: journal.amounts[0],
+                yes: journal.amounts[1],
+                no: journal.amounts[2]
+            });
+            require(
+                amounts.abstain + amounts.yes + amounts.no == journal.balance,
+                "Invalid split vote"
+            );
+        } else {
+            require(journal.amounts.length == 0, "Invalid split vote");
+            if (_voteOption == VoteOption.Yes) {
+                amounts.yes = journal.balance;
+            } else if (_voteOption == VoteOption.No) {
+                amounts.no = journal.balance;
+            } else if (_voteOption == VoteOption.Abstain) {
+                amounts.abstain = journal.balance;
+            }
+        }
+    }
+
+    /// @notice Emits a `VoteCast` event for one option of a split vote, unless it got no voting power.
+    function _emitSplitVoteCast(
+        uint256 _proposalId,
+        address _voter,
+        VoteOption _voteOption,
+        uint256 _votingPower
+    ) internal {
+        if (_votingPower != 0) {
+            emit VoteCast({
+                proposalId: _proposalId,
+                voter: _voter,
+                voteOption: _voteOption,
+                votingPower: _votingPower
+            });
+        }
+    }
+
     /// @inheritdoc MajorityVotingBase
     function _canVote(
         uint256 _proposalId,
@@ -200,9 +580,12 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
             return false;
         }
 
-        // The voter has already voted but vote replacment is not allowed.
+        // The voter has already voted, possibly with a split vote, but vote replacment is not allowed.
+        Tally storage voterTally = proposal_.voterTallies[_account];
+        bool hasVoted = proposal_.voters[_account] != VoteOption.None ||
+            voterTally.abstain + voterTally.yes + voterTally.no != 0;
         if (
-            proposal_.voters[_account] != VoteOption.None &&
+            hasVoted &&
             proposal_.parameters.votingMode != VotingMode.VoteReplacement
         ) {
             return false;
@@ -216,9 +599,9 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
         bytes calldata journalData,
         bytes calldata seal
     ) public override {
-        ExecutionJournal memory executionJournal = abi.decode(
+        Journal.ExecutionJournal memory executionJournal = abi.decode(
             journalData,
-            (ExecutionJournal)
+            (Journal.ExecutionJournal)
         );
         require(
             executionJournal.configContract == address(this),
@@ -231,18 +614,44 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
             revert ProposalExecutionForbidden(_proposalId);
         }
 
-        require(
-            executionJournal.commitment.digest ==
-                proposal_.parameters.snapshotBlockHash,
-            "Invalid commitment"
+        _validateCommitment(
+            executionJournal.commitment,
+            executionJournal.chainCommitments,
+            _proposalId
         );
-        (uint240 claimID, uint16 _version) = Encoding.decodeVersionedID(
-            executionJournal.commitment.id
+        require(
+            executionJournal.configHash ==
+                proposal_.parameters.votingProtocolConfigHash,
+            "Invalid config hash"
         );
+
+        // The proven tally must be the one recorded on-chain, evaluated with the strategy of the proposal's kind.
+        uint8 optionCount = proposal_.parameters.optionCount;
         require(
-            claimID == proposal_.parameters.snapshotBlock,
-            "Invalid commitment"
+            executionJournal.optionCount == optionCount,
+            "Invalid option count"
         );
+        if (optionCount != 0) {
+            require(
+                keccak256(abi.encodePacked(executionJournal.tally)) ==
+                    keccak256(abi.encodePacked(proposal_.ballots)),
+                "Invalid tally"
+            );
+            require(
+                executionJournal.winner != 0 &&
+                    executionJournal.winner <= optionCount,
+                "Invalid winner"
+            );
+        } else {
+            require(
+                executionJournal.tally.length == 3 &&
+                    executionJournal.tally[0] == proposal_.tally.yes &&
+                    executionJournal.tally[1] == proposal_.tally.no &&
+                    executionJournal.tally[2] == proposal_.tally.abstain &&
+                    executionJournal.winner == 0,
+                "Invalid tally"
+            );
+        }
 
         // Verify the proof
         bytes32 journalHash = sha256(journalData);
@@ -252,12 +661,144 @@ contract RiscVotingProtocolPlugin is MajorityVotingBase {
             journalHash
         );
 
+        if (optionCount != 0) {
+            uint8 winner = executionJournal.winner;
+            proposal_.winningOption = winner;
+            emit WinningOptionChosen(_proposalId, winner);
+
+            // Only the actions of the winning option run.
+            IDAO.Action[] storage winnerActions = proposal_.optionActions[
+                winner
+            ];
+            for (uint256 i; i < winnerActions.length; ) {
+                proposal_.actions.push(winnerActions[i]);
+                unchecked {
+                    ++i;
+                }
+            }
+        }
         _execute(_proposalId);
     }
 
+    /// @notice Checks that the Steel commitments of a journal refer to the proposal's snapshot blocks.
+    /// @dev Block commitments (version 0) are checked against the stored snapshot block hash and beacon commitments
+    /// (version 1) against the stored beacon block root, so neither goes stale after 256 blocks or the EIP-4788 window.
+    /// @param commitment The commitment of the journal.
+    /// @param chainCommitments The commitments of the journal to other chains.
+    /// @param _proposalId The ID of the proposal.
+    function _validateCommitment(
+        Steel.Commitment memory commitment,
+        Journal.ChainCommitment[] memory chainCommitments,
+        uint256 _proposalId
+    ) internal view {
+        ProposalParameters storage parameters = proposals[_proposalId]
+            .parameters;
+        (uint240 claimID, uint16 version) = Encoding.decodeVersionedID(
+            commitment.id
+        );
+        if (version == 0) {
+            require(
+                claimID == parameters.snapshotBlock &&
+                    commitment.digest == parameters.snapshotBlockHash,
+                "Invalid commitment"
+            );
+        } else if (version == 1) {
+            require(
+                parameters.snapshotBeaconRoot != bytes32(0) &&
+                    claimID == parameters.snapshotBeaconTimestamp &&
+                    commitment.digest == parameters.snapshotBeaconRoot,
+                "Invalid commitment"
+            );
+        } else {
+            revert("Invalid commitment version");
+        }
+        _validateChainCommitments(chainCommitments, _proposalId);
+    }
+
+    /// @notice Checks that a journal commits to exactly the blocks of the other chains pinned by the proposal.
+    /// @dev The guests commit one block commitment per chain, in ascending chain ID order, like `chainIds`.
+    /// @param chainCommitments The commitments of the journal to other chains.
+    /// @param _proposalId The ID of the proposal.
+    function _validateChainCommitments(
+        Journal.ChainCommitment[] memory chainCommitments,
+        uint256 _proposalId
+    ) internal view {
+        ChainBlock[] storage chainBlocks = proposalChainBlocks[_proposalId];
+        require(
+            chainCommitments.length == chainBlocks.length,
+            "Invalid chain commitments"
+        );
+        for (uint256 i; i < chainCommitments.length; ) {
+            (uint240 claimID, uint16 version) = Encoding.decodeVersionedID(
+                chainCommitments[i].commitment.id
+            );
+            require(
+                chainCommitments[i].chainId == chainBlocks[i].chainId &&
+                    version == 0 &&
+                    claimID == chainBlocks[i].blockNumber &&
+                    chainCommitments[i].commitment.digest ==
+                    chainBlocks[i].blockHash,
+                "Invalid chain commitments"
+            );
+            unchecked {
+                ++i;
+            }
+        }
+    }
+
+    /// @notice Sets or removes the oracle of another chain, keeping `chainIds` in ascending order.
+    /// @param _chainId The chain ID.
+    /// @param _oracle The oracle of the chain, or zero to remove it.
+    function _setChainOracle(
+        uint64 _chainId,
+        IChainBlockOracle _oracle
+    ) internal {
+        require(_chainId != block.chainid, "Invalid chain");
+
+        bool listed = address(chainOracles[_chainId]) != address(0);
+        chainOracles[_chainId] = _oracle;
+        if (address(_oracle) != address(0) && !listed) {
+            chainIds.push(_chainId);
+            for (uint256 i = chainIds.length - 1; i > 0 && chainIds[i - 1] > _chainId; ) {
+                chainIds[i] = chainIds[i - 1];
+                chainIds[i - 1] = _chainId;
+                unchecked {
+                    --i;
+                }
+            }
+        } else if (address(_oracle) == address(0) && listed) {
+            uint256 i;
+            while (chainIds[i] != _chainId) {
+                unchecked {
+                    ++i;
+                }
+            }
+            for (; i + 1 < chainIds.length; ) {
+                chainIds[i] = chainIds[i + 1];
+                unchecked {
+                    ++i;
+                }
+            }
+            chainIds.pop();
+        }
+
+        emit ChainOracleUpdated(_chainId, _oracle);
+    }
+
+    /// @notice Reads the beacon block root of the snapshot block from the EIP-4788 contract.
+    /// @return root The beacon block root, or zero on chains without EIP-4788.
+    function _snapshotBeaconRoot() internal view returns (bytes32 root) {
+        (bool success, bytes memory result) = Beacon
+            .BEACON_ROOTS_ADDRESS
+            .staticcall(abi.encode(block.timestamp));
+        if (success && result.length == 32) {
+            root = abi.decode(result, (bytes32));
+        }
+    }
+
     // TODO: Revisit this number
     /// @dev This empty reserved space is put in place to allow future versions to add new
     /// variables without shifting down storage in the inheritance chain.
     /// https://docs.openzeppelin.com/contracts/4.x/upgradeable#storage_gaps
-    uint256[49] private __gap;
+    uint256[45] private __gap;
 }
@@ -0,0 +1,111 @@
+use std::{env, fs, path::Path, str::FromStr};
+
+use proc_macro2::TokenStream;
+use similar::TextDiff;
+use syn::{Attribute, Expr, Lit, Meta};
+use syn_solidity::{Item, ItemStruct, Type};
+
+// Solidity definitions of the journals, shared with the Rust crate.
+const JOURNAL_SOL_PATH: &str = "journal.sol";
+
+// Path of the checked-in Solidity library the generated one must match.
+const SOLIDITY_JOURNAL_PATH: &str = "../contracts/Journal.sol";
+
+// Set to write the generated library to `SOLIDITY_JOURNAL_PATH` instead of checking it.
+const UPDATE_ENV: &str = "UPDATE_JOURNAL_SOL";
+
+const HEADER: &str = "// SPDX-License-Identifier: AGPL-3.0-or-later
+// This file is automatically generated by the `journal` crate. Do not edit it by hand.
+
+pragma solidity ^0.8.17;
+
+import {Steel} from \"risc0/steel/Steel.sol\";
+
+/// @title Journal
+/// @notice ABI encodable journals committed by the guests.
+library Journal {
+";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", JOURNAL_SOL_PATH);
+    println!("cargo:rerun-if-changed={}", SOLIDITY_JOURNAL_PATH);
+    println!("cargo:rerun-if-env-changed={}", UPDATE_ENV);
+
+    let definitions = fs::read_to_string(JOURNAL_SOL_PATH).unwrap();
+    let tokens = TokenStream::from_str(&definitions).unwrap();
+    let file = syn_solidity::parse2(tokens).expect("journal.sol is not valid Solidity");
+
+    let structs: Vec<String> = file
+        .items
+        .iter()
+        .map(|item| match item {
+            Item::Struct(item) => solidity_struct(item),
+            _ => panic!("journal.sol may only define structs"),
+        })
+        .collect();
+    let solidity = format!("{}{}}}\n", HEADER, structs.join("\n"));
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("Journal.sol");
+    fs::write(&out_path, &solidity).unwrap();
+
+    if env::var_os(UPDATE_ENV).is_some() {
+        fs::write(SOLIDITY_JOURNAL_PATH, &solidity).unwrap();
+        return;
+    }
+    let checked_in = fs::read_to_string(SOLIDITY_JOURNAL_PATH).unwrap_or_default();
+    if checked_in != solidity {
+        let diff = TextDiff::from_lines(&checked_in, &solidity)
+            .unified_diff()
+            .header(SOLIDITY_JOURNAL_PATH, &out_path.display().to_string())
+            .to_string();
+        panic!(
+            "{} is out of date with {}, rebuild with {}=1 to update it:\n{}",
+            SOLIDITY_JOURNAL_PATH, JOURNAL_SOL_PATH, UPDATE_ENV, diff
+        );
+    }
+}
+
+/// Prints a struct of `journal.sol` as a member of the `Journal` library.
+fn solidity_struct(item: &ItemStruct) -> String {
+    let mut solidity = String::new();
+    for doc in docs(&item.attrs) {
+        solidity.push_str(&format!("    ///{}\n", doc));
+    }
+    solidity.push_str(&format!("    struct {} {{\n", item.name));
+    for field in item.fields.iter() {
+        let name = field.name.as_ref().expect("struct fields must be named");
+        solidity.push_str(&format!("        {} {};\n", solidity_type(&field.ty), name));
+    }
+    solidity.push_str("    }\n");
+    solidity
+}
+
+/// Names a type from the library. Steel commitments live in the `Steel` library there.
+fn solidity_type(ty: &Type) -> String {
+    match ty {
+        Type::Custom(path) if path.to_string() == "Commitment" => "Steel.Commitment".to_string(),
+        Type::Array(array) => match array.size() {
+            Some(size) => format!("{}[{}]", solidity_type(&array.ty), size),
+            None => format!("{}[]", solidity_type(&array.ty)),
+        },
+        ty => ty.to_string(),
+    }
+}
+
+/// Returns the lines of the doc comments among `attrs`, keeping their leading space.
+fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .map(|attr| match &attr.meta {
+            Meta::NameValue(doc) => match &doc.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(doc) => doc.value(),
+                    _ => panic!("unsupported doc comment"),
+                },
+                _ => panic!("unsupported doc comment"),
+            },
+            _ => panic!("unsupported doc comment"),
+        })
+        .collect()
+}
@@ -0,0 +1,77 @@
+//! Journals committed by the guests, shared by the guests, the host tooling and the tests.
+//!
+//! The definitions live in `journal.sol`. The build script also generates the Solidity library
+//! the plugin decodes them with, and fails when the checked-in `contracts/Journal.sol` differs
+//! from it. Build with `UPDATE_JOURNAL_SOL=1` to update that file.
+
+use risc0_steel::Commitment;
+
+alloy_sol_types::sol!("journal.sol");
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, B256, U256};
+    use alloy_sol_types::SolValue;
+
+    use super::*;
+
+    #[test]
+    fn test_voting_journal_roundtrip() {
+        let journal = VotingJournal {
+            commitment: Commitment {
+                id: U256::from(7087022),
+                digest: B256::repeat_byte(1),
+            },
+            configContract: address!("af4ba5015Eb5bE8780e664e2BE40144668361B0f"),
+            configHash: B256::repeat_byte(3),
+            proposalId: U256::from(0),
+            voter: address!("8bF1e340055c7dE62F11229A149d3A1918de3d74"),
+            balance: U256::from(450000000000000000u64),
+            direction: 0,
+            amounts: vec![
+                U256::from(50000000000000000u64),
+                U256::from(300000000000000000u64),
+                U256::from(100000000000000000u64),
+            ],
+            choices: vec![],
+            lookbackCommitments: vec![],
+            chainCommitments: vec![ChainCommitment {
+                chainId: 10,
+                commitment: Commitment {
+                    id: U256::from(1),
+                    digest: B256::repeat_byte(2),
+                },
+            }],
+        };
+
+        let decoded = VotingJournal::abi_decode(&journal.abi_encode(), true).unwrap();
+        assert_eq!(decoded.configHash, journal.configHash);
+        assert_eq!(decoded.voter, journal.voter);
+        assert_eq!(decoded.balance, journal.balance);
+        assert_eq!(decoded.amounts, journal.amounts);
+        assert_eq!(decoded.chainCommitments[0].chainId, 10);
+    }
+
+    #[test]
+    fn test_execution_journal_roundtrip() {
+        let journal = ExecutionJournal {
+            commitment: Commitment {
+                id: U256::from(7087022),
+                digest: B256::repeat_byte(1),
+            },
+            configContract: address!("af4ba5015Eb5bE8780e664e2BE40144668361B0f"),
+            configHash: B256::repeat_byte(3),
+            proposalId: U256::from(1),
+            optionCount: 2,
+            tally: vec![U256::from(3), U256::from(2), U256::from(1), U256::from(1)],
+            winner: 2,
+            chainCommitments: vec![],
+        };
+
+        let decoded = ExecutionJournal::abi_decode(&journal.abi_encode(), true).unwrap();
+        assert_eq!(decoded.proposalId, journal.proposalId);
+        assert_eq!(decoded.optionCount, journal.optionCount);
+        assert_eq!(decoded.tally, journal.tally);
+        assert_eq!(decoded.winner, journal.winner);
+    }
+}
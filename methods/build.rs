@@ -28,14 +28,31 @@ fn main() {
         root_dir: Some("../".into()),
     });
 
-    // Generate Rust source files for the methods crate.
-    let guests = embed_methods_with_options(HashMap::from([(
-        "aragon-zk-voting-protocol-guests",
-        GuestOptions {
-            features: Vec::new(),
-            use_docker,
-        },
-    )]));
+    // The guests print the cycles spent loading the config when RISC0_CYCLE_COUNT is set.
+    println!("cargo:rerun-if-env-changed=RISC0_CYCLE_COUNT");
+    let features = match env::var("RISC0_CYCLE_COUNT") {
+        Ok(_) => vec!["cycle-count".to_string()],
+        Err(_) => Vec::new(),
+    };
+
+    // Generate Rust source files for the methods crate. The current guests are built next to the
+    // frozen guests of earlier versions.
+    let guests = embed_methods_with_options(HashMap::from([
+        (
+            "aragon-zk-voting-protocol-guests",
+            GuestOptions {
+                features: features.clone(),
+                use_docker: use_docker.clone(),
+            },
+        ),
+        (
+            "aragon-zk-voting-protocol-guests-v1",
+            GuestOptions {
+                features,
+                use_docker,
+            },
+        ),
+    ]));
 
     // Generate Solidity source files for use with Forge.
     let solidity_opts = risc0_build_ethereum::Options::default()
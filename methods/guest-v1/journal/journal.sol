@@ -0,0 +1,53 @@
+/// @notice Commitment to the state of another chain holding assets of the census.
+struct ChainCommitment {
+    uint64 chainId;
+    Commitment commitment;
+}
+
+/// @notice Journal committed by the voting guest.
+/// @dev A split vote has `direction` 0 and spreads `balance` over `amounts`, the voting power given to the
+/// abstain, yes and no options. `amounts` is empty for a vote with a single direction.
+/// A ballot of a multi-option proposal has `direction` 0 and lists its `choices`, options numbered from 1,
+/// most preferred first. `choices` is empty for other votes.
+struct VotingJournal {
+    Commitment commitment;
+    address configContract;
+    bytes32 configHash;
+    uint256 proposalId;
+    address voter;
+    uint256 balance;
+    uint8 direction;
+    uint256[] amounts;
+    uint8[] choices;
+    Commitment[] lookbackCommitments;
+    ChainCommitment[] chainCommitments;
+}
+
+/// @notice Journal committed by the execution guest.
+/// @dev `optionCount` is the proposal's number of options, which selects the execution strategy of its kind, or 0
+/// for majority proposals. `tally` is yes, no and abstain for majority proposals, and the recorded ballots for
+/// multi-option proposals. `winner` is the option chosen by the execution strategy, numbered from 1, or 0 for
+/// majority proposals.
+struct ExecutionJournal {
+    Commitment commitment;
+    address configContract;
+    bytes32 configHash;
+    uint256 proposalId;
+    uint8 optionCount;
+    uint256[] tally;
+    uint8 winner;
+    ChainCommitment[] chainCommitments;
+}
+
+/// @notice Journal committed by the proposal guest.
+/// @dev `paramsHash` is the plugin's `proposalParamsHash` of the proposal the proof can create.
+struct ProposalJournal {
+    Commitment commitment;
+    address configContract;
+    bytes32 configHash;
+    address proposer;
+    bytes32 paramsHash;
+    uint256 votingPower;
+    Commitment[] lookbackCommitments;
+    ChainCommitment[] chainCommitments;
+}
@@ -0,0 +1,5 @@
+//! Frozen copy of the journals committed by the version 1 guests.
+
+use risc0_steel::Commitment;
+
+alloy_sol_types::sol!("journal.sol");
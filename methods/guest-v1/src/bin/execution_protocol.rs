@@ -0,0 +1,132 @@
+#![allow(unused_doc_comments)]
+#![no_main]
+
+use alloy_primitives::U256;
+use alloy_sol_types::{sol, SolValue};
+use journal::{ChainCommitment, ExecutionJournal};
+use risc0_steel::{ethereum::ETH_SEPOLIA_CHAIN_SPEC, Contract};
+use risc0_zkvm::guest::env;
+use strategies::input::ExecutionInput;
+
+risc0_zkvm::guest::entry!(main);
+
+/// Specify the function to call using the [`sol!`] macro.
+/// This parses the Solidity syntax to generate a struct that implements the `SolCall` trait.
+sol! {
+    interface ConfigContract {
+        function votingProtocolConfigHash() external view returns (bytes32);
+    }
+}
+
+fn main() {
+    // Read the input from the guest environment.
+    let input: ExecutionInput = env::read();
+    assert_eq!(
+        input.version,
+        ExecutionInput::VERSION,
+        "Unsupported execution input version"
+    );
+    let ExecutionInput {
+        evm_input,
+        chain_inputs,
+        proposal_id,
+        config_contract,
+        config,
+        option_count,
+        tally,
+        ..
+    } = input;
+
+    // Converts the input into a `EvmEnv` for execution. The `with_chain_spec` method is used
+    // to specify the chain configuration. It checks that the state matches the state root in the
+    // header provided in the input.
+    let destination_chain_id = &ETH_SEPOLIA_CHAIN_SPEC;
+    let env = evm_input.into_env().with_chain_spec(destination_chain_id);
+
+    // The config comes with the input in binary form, which is cheaper than reading the string
+    // from storage through Steel and parsing it. Its canonical JSON form is checked against the
+    // hash stored by the plugin. The proposal is created after its snapshot block, so this is the
+    // plugin's hash, which the proposal copies; the plugin checks the journal against the
+    // proposal's own copy.
+    #[cfg(feature = "cycle-count")]
+    let config_start = env::cycle_count();
+    let config_hash_call = ConfigContract::votingProtocolConfigHashCall {};
+    let config_hash_returns = Contract::new(config_contract, &env)
+        .call_builder(&config_hash_call)
+        .call();
+    let config = strategies::RiscVotingProtocolConfig::from_bytes(&config).expect("Invalid config");
+    let config_hash = strategies::config_hash(&config.to_json());
+    assert_eq!(
+        config_hash, config_hash_returns._0,
+        "Config does not match the hash stored by the plugin"
+    );
+    assert_eq!(
+        config.voting_protocol_version,
+        strategies::VOTING_PROTOCOL_VERSION,
+        "Config targets another version of the voting protocol"
+    );
+    #[cfg(feature = "cycle-count")]
+    println!(
+        "Config loaded in {} cycles",
+        env::cycle_count() - config_start
+    );
+
+    let mut strategies_context = strategies::Context::default(env);
+    strategies_context.configure_execution_strategies(&config);
+
+    // Assets on other chains are evaluated against their own environment.
+    let chain_ids: Vec<u64> = chain_inputs.iter().map(|(chain_id, _)| *chain_id).collect();
+    assert!(config.assets.iter().all(|asset| {
+        asset.chain_id == destination_chain_id.chain_id() || chain_ids.contains(&asset.chain_id)
+    }));
+    for (chain_id, input) in chain_inputs {
+        let chain_env = input
+            .into_env()
+            .with_chain_spec(&strategies::chain_spec(chain_id));
+        strategies_context.add_chain_env(chain_id, chain_env);
+    }
+
+    // Get the total voting power of the voter across all assets.
+    let total_voting_power = config
+        .assets
+        .iter()
+        .map(|asset| strategies_context.process_total_supply(asset))
+        .sum::<U256>();
+
+    // General settings constraints. Multi-option proposals are executed with their own strategy,
+    // whose winner must be one of the options; the plugin checks the option count.
+    let execution_strategy = config
+        .execution_strategy_for(option_count)
+        .expect("Config has no execution strategy for multi-option proposals")
+        .to_string();
+    config
+        .check_execution_parameters(&execution_strategy)
+        .expect("Invalid execution strategy parameters");
+    let winner = strategies_context.process_execution_winner(execution_strategy.clone(), &tally);
+    assert!(winner <= option_count, "Winner is not an option");
+    assert!(strategies_context.process_execution_strategy(
+        execution_strategy,
+        total_voting_power,
+        &tally
+    ));
+
+    // Commit the block hash and number used when deriving `view_call_env` to the journal.
+    let journal = ExecutionJournal {
+        commitment: strategies_context.block_commitment(),
+        configContract: config_contract,
+        configHash: config_hash,
+        proposalId: proposal_id,
+        optionCount: option_count,
+        tally,
+        winner,
+        chainCommitments: strategies_context
+            .chain_commitments()
+            .into_iter()
+            .map(|(chain_id, commitment)| ChainCommitment {
+                chainId: chain_id,
+                commitment,
+            })
+            .collect(),
+    };
+    env::commit_slice(&journal.abi_encode());
+}
@@ -0,0 +1,160 @@
+#![allow(unused_doc_comments)]
+#![no_main]
+
+use std::str::FromStr;
+
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::{sol, SolValue};
+use journal::{ChainCommitment, ProposalJournal};
+use risc0_steel::{ethereum::ETH_SEPOLIA_CHAIN_SPEC, Contract};
+use risc0_zkvm::guest::env;
+use strategies::{
+    input::ProposalInput,
+    voting_strategies::{MerkleCensus, MinBalanceOf},
+};
+
+risc0_zkvm::guest::entry!(main);
+
+/// Specify the function to call using the [`sol!`] macro.
+/// This parses the Solidity syntax to generate a struct that implements the `SolCall` trait.
+sol! {
+    interface ConfigContract {
+        function votingProtocolConfigHash() external view returns (bytes32);
+        function minProposerVotingPower() external view returns (uint256);
+    }
+}
+
+fn main() {
+    // Read the input from the guest environment.
+    let input: ProposalInput = env::read();
+    assert_eq!(
+        input.version,
+        ProposalInput::VERSION,
+        "Unsupported proposal input version"
+    );
+    let ProposalInput {
+        evm_input,
+        proposer,
+        params_hash,
+        config_contract,
+        config,
+        additional_delegation_data,
+        census_proofs,
+        ancestors,
+        lookback_inputs,
+        chain_inputs,
+        ..
+    } = input;
+
+    // Converts the input into a `EvmEnv` for execution. The `with_chain_spec` method is used
+    // to specify the chain configuration. It checks that the state matches the state root in the
+    // header provided in the input.
+    let destination_chain_id = &ETH_SEPOLIA_CHAIN_SPEC;
+    let env = evm_input.into_env().with_chain_spec(destination_chain_id);
+
+    // Look-back blocks must be ancestors of the proving block.
+    let lookback_envs = lookback_inputs
+        .into_iter()
+        .map(|input| input.into_env().with_chain_spec(destination_chain_id))
+        .collect();
+    let min_balance_of = MinBalanceOf::new(&env, ancestors, lookback_envs);
+    let lookback_commitments = min_balance_of.commitments();
+
+    // There is no proposal yet, so the plugin's current config and threshold apply.
+    #[cfg(feature = "cycle-count")]
+    let config_start = env::cycle_count();
+    let config_hash_call = ConfigContract::votingProtocolConfigHashCall {};
+    let config_hash_returns = Contract::new(config_contract, &env)
+        .call_builder(&config_hash_call)
+        .call();
+    let config = strategies::RiscVotingProtocolConfig::from_bytes(&config).expect("Invalid config");
+    let config_hash = strategies::config_hash(&config.to_json());
+    assert_eq!(
+        config_hash, config_hash_returns._0,
+        "Config does not match the hash stored by the plugin"
+    );
+    assert_eq!(
+        config.voting_protocol_version,
+        strategies::VOTING_PROTOCOL_VERSION,
+        "Config targets another version of the voting protocol"
+    );
+    #[cfg(feature = "cycle-count")]
+    println!(
+        "Config loaded in {} cycles",
+        env::cycle_count() - config_start
+    );
+
+    let min_power_call = ConfigContract::minProposerVotingPowerCall {};
+    let min_proposer_voting_power = Contract::new(config_contract, &env)
+        .call_builder(&min_power_call)
+        .call()
+        ._0;
+
+    let mut strategies_context = strategies::Context::default(env);
+    strategies_context.add_strategy(
+        "MerkleCensus".to_string(),
+        Box::new(MerkleCensus::new(census_proofs)),
+    );
+
+    // Assets on other chains are evaluated against their own environment.
+    let chain_ids: Vec<u64> = chain_inputs.iter().map(|(chain_id, _)| *chain_id).collect();
+    assert!(config.assets.iter().all(|asset| {
+        asset.chain_id == destination_chain_id.chain_id() || chain_ids.contains(&asset.chain_id)
+    }));
+    for (chain_id, input) in chain_inputs {
+        let chain_env = input
+            .into_env()
+            .with_chain_spec(&strategies::chain_spec(chain_id));
+        strategies_context.add_chain_env(chain_id, chain_env);
+    }
+    strategies_context.add_strategy("MinBalanceOf".to_string(), Box::new(min_balance_of));
+
+    // Get the total voting power of the proposer across all assets.
+    let total_voting_power: U256 = config
+        .assets
+        .iter()
+        .map(|asset| {
+            // Get the accounts whose voting power is delegated to the proposer.
+            let delegations = strategies_context
+                .process_delegation_strategy(
+                    proposer,
+                    asset,
+                    Bytes::from_str(additional_delegation_data.as_str()).unwrap(),
+                )
+                .expect("Delegations given are not correct");
+            delegations.iter().fold(U256::from(0), |acc, delegation| {
+                (strategies_context.process_voting_strategy(
+                    asset.voting_power_strategy.clone(),
+                    delegation.delegate,
+                    asset,
+                ) / delegation.ratio)
+                    + acc
+            })
+        })
+        .sum::<U256>();
+
+    assert!(
+        total_voting_power >= min_proposer_voting_power,
+        "Proposer holds less than the minimum proposer voting power"
+    );
+
+    // Commit the block hash and number used when deriving `view_call_env` to the journal.
+    let journal = ProposalJournal {
+        commitment: strategies_context.block_commitment(),
+        configContract: config_contract,
+        configHash: config_hash,
+        proposer,
+        paramsHash: params_hash,
+        votingPower: total_voting_power,
+        lookbackCommitments: lookback_commitments,
+        chainCommitments: strategies_context
+            .chain_commitments()
+            .into_iter()
+            .map(|(chain_id, commitment)| ChainCommitment {
+                chainId: chain_id,
+                commitment,
+            })
+            .collect(),
+    };
+    env::commit_slice(&journal.abi_encode());
+}
@@ -0,0 +1,111 @@
+use crate::{GuestEvmEnv, Ratio};
+use alloy_primitives::U256;
+
+pub trait ProtocolExecutionStrategy {
+    fn proof_execution(&self, env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool;
+
+    /// Option chosen by the voters, numbered from 1, or 0 for strategies that only accept or
+    /// reject the proposal.
+    fn winner(&self, _tally: &[U256]) -> u8 {
+        0
+    }
+}
+
+mod absolute_quorum;
+mod approval_voting;
+mod majority_voting;
+mod optimistic;
+mod ranked_choice;
+mod supermajority;
+
+pub use absolute_quorum::{absolute_quorum_reached, AbsoluteQuorum};
+pub use approval_voting::{approval_winner, ApprovalVoting};
+pub use majority_voting::MajorityVoting;
+pub use optimistic::{vetoed, Optimistic};
+pub use ranked_choice::{instant_runoff_winner, RankedChoice};
+pub use supermajority::{supermajority_reached, Supermajority};
+
+/// Maximum number of choices of a ballot, one byte each in a packed word.
+pub const MAX_BALLOT_CHOICES: usize = 32;
+
+/// A ballot of a multi-option proposal: the voting power behind it and the chosen options,
+/// numbered from 1 and most preferred first.
+pub type Ballot = (U256, Vec<u8>);
+
+/// Packs the choices of a ballot like the plugin does, the first choice in the lowest byte.
+pub fn pack_choices(choices: &[u8]) -> U256 {
+    assert!(choices.len() <= MAX_BALLOT_CHOICES, "Too many choices");
+    let mut bytes = [0u8; 32];
+    bytes[..choices.len()].copy_from_slice(choices);
+    U256::from_le_bytes(bytes)
+}
+
+/// Reverse of [`pack_choices`].
+pub fn unpack_choices(packed: U256) -> Vec<u8> {
+    packed
+        .to_le_bytes::<32>()
+        .into_iter()
+        .take_while(|choice| *choice != 0)
+        .collect()
+}
+
+/// Decodes the tally of a multi-option proposal, which the plugin records as a voting power and
+/// packed choices pair per ballot. Returns `None` if the tally is not made of such pairs.
+pub fn decode_ballots(tally: &[U256]) -> Option<Vec<Ballot>> {
+    if tally.len() % 2 == 1 {
+        return None;
+    }
+    Some(
+        tally
+            .chunks(2)
+            .map(|ballot| (ballot[0], unpack_choices(ballot[1])))
+            .collect(),
+    )
+}
+
+/// Whether the ballots of a multi-option `tally` carry at least `min_participation` of
+/// `total_supply`. Each ballot counts once, however many options it chooses.
+pub fn ballot_participation_reached(
+    total_supply: U256,
+    tally: &[U256],
+    min_participation: &Ratio,
+) -> bool {
+    let Some(ballots) = decode_ballots(tally) else {
+        return false;
+    };
+    let Some(participation) = ballots
+        .iter()
+        .try_fold(U256::ZERO, |total, (voting_power, _)| {
+            total.checked_add(*voting_power)
+        })
+    else {
+        return false;
+    };
+    min_participation.reached(participation, total_supply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ballot_participation_counts_each_ballot_once() {
+        let half = Ratio {
+            numerator: 1,
+            denominator: 2,
+        };
+        let tally = [
+            U256::from(3),
+            pack_choices(&[1, 2, 3]),
+            U256::from(2),
+            pack_choices(&[2]),
+        ];
+        assert!(ballot_participation_reached(U256::from(10), &tally, &half));
+        assert!(!ballot_participation_reached(U256::from(11), &tally, &half));
+        assert!(!ballot_participation_reached(
+            U256::from(10),
+            &tally[..3],
+            &half
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::GuestEvmEnv;
+
+use super::ProtocolExecutionStrategy;
+use alloy_primitives::U256;
+
+/// Passes when the yes votes reach the config's absolute `quorum`, whatever the other votes.
+#[derive(Default)]
+pub struct AbsoluteQuorum {
+    quorum: Option<U256>,
+}
+
+impl AbsoluteQuorum {
+    pub fn new(quorum: Option<U256>) -> Self {
+        Self { quorum }
+    }
+}
+
+impl ProtocolExecutionStrategy for AbsoluteQuorum {
+    fn proof_execution(&self, _env: &GuestEvmEnv, _total_supply: U256, tally: &[U256]) -> bool {
+        match self.quorum {
+            Some(quorum) => absolute_quorum_reached(tally, quorum),
+            None => false,
+        }
+    }
+}
+
+/// Whether the yes votes of a yes, no and abstain `tally` are at least `quorum`, which must not
+/// be zero.
+pub fn absolute_quorum_reached(tally: &[U256], quorum: U256) -> bool {
+    let [yes, _, _] = tally else {
+        return false;
+    };
+    quorum > U256::ZERO && *yes >= quorum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quorum_boundary_is_inclusive() {
+        let quorum = U256::from(1000);
+        let no = U256::from(5000);
+        assert!(absolute_quorum_reached(
+            &[U256::from(1000), no, U256::ZERO],
+            quorum
+        ));
+        assert!(!absolute_quorum_reached(
+            &[U256::from(999), U256::ZERO, U256::ZERO],
+            quorum
+        ));
+        assert!(!absolute_quorum_reached(
+            &[U256::ZERO, U256::ZERO, U256::ZERO],
+            U256::ZERO
+        ));
+        assert!(!absolute_quorum_reached(&[U256::from(1000)], quorum));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{GuestEvmEnv, Ratio};
+
+use super::{ballot_participation_reached, decode_ballots, ProtocolExecutionStrategy};
+use alloy_primitives::U256;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Ballots rank the options, and the winner is found by instant-runoff rounds once the ballots
+/// reach the config's `minParticipation` of the total supply.
+#[derive(Default)]
+pub struct RankedChoice {
+    min_participation: Option<Ratio>,
+}
+
+impl RankedChoice {
+    pub fn new(min_participation: Option<Ratio>) -> Self {
+        Self { min_participation }
+    }
+}
+
+impl ProtocolExecutionStrategy for RankedChoice {
+    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool {
+        match &self.min_participation {
+            Some(min_participation) => {
+                ballot_participation_reached(total_supply, tally, min_participation)
+                    && instant_runoff_winner(tally) != 0
+            }
+            None => false,
+        }
+    }
+
+    fn winner(&self, tally: &[U256]) -> u8 {
+        instant_runoff_winner(tally)
+    }
+}
+
+/// Runs instant-runoff rounds over ranked ballots. Each round, every ballot counts for its most
+/// preferred option still in the race. An option backed by more than half of the counted voting
+/// power wins; otherwise the options with the fewest votes are eliminated together. Returns 0
+/// if there are no votes or the last options are tied.
+pub fn instant_runoff_winner(tally: &[U256]) -> u8 {
+    let Some(ballots) = decode_ballots(tally) else {
+        return 0;
+    };
+
+    let mut remaining: BTreeSet<u8> = ballots
+        .iter()
+        .flat_map(|(_, choices)| choices.iter().copied())
+        .collect();
+
+    while !remaining.is_empty() {
+        let mut votes: BTreeMap<u8, U256> = remaining
+            .iter()
+            .map(|option| (*option, U256::ZERO))
+            .collect();
+        let mut counted = U256::ZERO;
+        for (voting_power, choices) in &ballots {
+            if let Some(choice) = choices.iter().find(|choice| remaining.contains(choice)) {
+                *votes.get_mut(choice).unwrap() += *voting_power;
+                counted += *voting_power;
+            }
+        }
+
+        if let Some((option, _)) = votes.iter().find(|(_, votes)| **votes > counted - **votes) {
+            return *option;
+        }
+
+        let fewest_votes = *votes.values().min().unwrap();
+        remaining.retain(|option| votes[option] != fewest_votes);
+    }
+
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_strategies::pack_choices;
+
+    #[test]
+    fn test_first_round_majority_wins() {
+        let tally = [
+            U256::from(6),
+            pack_choices(&[3, 1]),
+            U256::from(4),
+            pack_choices(&[1, 3]),
+        ];
+        assert_eq!(instant_runoff_winner(&tally), 3);
+    }
+
+    #[test]
+    fn test_runoff_transfers_eliminated_votes() {
+        let tally = [
+            U256::from(4),
+            pack_choices(&[1, 2]),
+            U256::from(3),
+            pack_choices(&[2]),
+            U256::from(2),
+            pack_choices(&[3, 2]),
+        ];
+        assert_eq!(instant_runoff_winner(&tally), 2);
+    }
+
+    #[test]
+    fn test_tie_has_no_winner() {
+        let tally = [
+            U256::from(4),
+            pack_choices(&[1]),
+            U256::from(4),
+            pack_choices(&[2]),
+        ];
+        assert_eq!(instant_runoff_winner(&tally), 0);
+        assert_eq!(instant_runoff_winner(&[U256::from(1)]), 0);
+    }
+}
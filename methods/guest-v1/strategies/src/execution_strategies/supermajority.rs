@@ -0,0 +1,150 @@
+use crate::{GuestEvmEnv, Ratio};
+
+use super::ProtocolExecutionStrategy;
+use alloy_primitives::U256;
+
+/// Passes when yes votes reach the config's `supportThreshold` of the non-abstain votes and all
+/// votes reach its `minParticipation` of the total supply.
+#[derive(Default)]
+pub struct Supermajority {
+    support_threshold: Option<Ratio>,
+    min_participation: Option<Ratio>,
+}
+
+impl Supermajority {
+    pub fn new(support_threshold: Option<Ratio>, min_participation: Option<Ratio>) -> Self {
+        Self {
+            support_threshold,
+            min_participation,
+        }
+    }
+}
+
+impl ProtocolExecutionStrategy for Supermajority {
+    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool {
+        match (&self.support_threshold, &self.min_participation) {
+            (Some(support_threshold), Some(min_participation)) => {
+                supermajority_reached(total_supply, tally, support_threshold, min_participation)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether a yes, no and abstain `tally` passes with at least `support_threshold` of the
+/// non-abstain votes saying yes and at least `min_participation` of `total_supply` voting.
+/// Both bounds are inclusive and compared exactly, without rounding. A tally without yes votes
+/// never passes.
+pub fn supermajority_reached(
+    total_supply: U256,
+    tally: &[U256],
+    support_threshold: &Ratio,
+    min_participation: &Ratio,
+) -> bool {
+    let [yes, no, abstain] = tally else {
+        return false;
+    };
+    let Some(non_abstain) = yes.checked_add(*no) else {
+        return false;
+    };
+    let Some(total_votes) = non_abstain.checked_add(*abstain) else {
+        return false;
+    };
+
+    *yes > U256::ZERO
+        && support_threshold.reached(*yes, non_abstain)
+        && min_participation.reached(total_votes, total_supply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_THIRDS: Ratio = Ratio {
+        numerator: 2,
+        denominator: 3,
+    };
+    const HALF: Ratio = Ratio {
+        numerator: 1,
+        denominator: 2,
+    };
+
+    fn tally(yes: u64, no: u64, abstain: u64) -> [U256; 3] {
+        [U256::from(yes), U256::from(no), U256::from(abstain)]
+    }
+
+    #[test]
+    fn test_support_boundary_is_inclusive() {
+        let total_supply = U256::from(3_000_000);
+        // Exactly two thirds of the non-abstain votes.
+        assert!(supermajority_reached(
+            total_supply,
+            &tally(2_000_000, 1_000_000, 0),
+            &TWO_THIRDS,
+            &HALF
+        ));
+        // One vote short, which a rounded 666667 parts per million threshold would misjudge.
+        assert!(!supermajority_reached(
+            total_supply,
+            &tally(1_999_999, 1_000_000, 1),
+            &TWO_THIRDS,
+            &HALF
+        ));
+    }
+
+    #[test]
+    fn test_abstain_counts_for_participation_only() {
+        let total_supply = U256::from(10);
+        // 2 yes and 1 no reach the support, but 3 of 10 is below half the supply.
+        assert!(!supermajority_reached(
+            total_supply,
+            &tally(2, 1, 0),
+            &TWO_THIRDS,
+            &HALF
+        ));
+        // Abstaining brings participation to exactly half.
+        assert!(supermajority_reached(
+            total_supply,
+            &tally(2, 1, 2),
+            &TWO_THIRDS,
+            &HALF
+        ));
+        // Only abstentions never pass.
+        assert!(!supermajority_reached(
+            total_supply,
+            &tally(0, 0, 10),
+            &TWO_THIRDS,
+            &HALF
+        ));
+    }
+
+    #[test]
+    fn test_large_values_do_not_overflow() {
+        let half = U256::MAX / U256::from(2);
+        assert!(supermajority_reached(
+            U256::MAX,
+            &[half, U256::ZERO, U256::ZERO],
+            &TWO_THIRDS,
+            &Ratio {
+                numerator: 1,
+                denominator: 3,
+            }
+        ));
+        assert!(!supermajority_reached(
+            U256::MAX,
+            &[U256::MAX, U256::from(1), U256::ZERO],
+            &TWO_THIRDS,
+            &HALF
+        ));
+        assert!(TWO_THIRDS.reached(U256::MAX, U256::MAX));
+    }
+
+    #[test]
+    fn test_zero_denominator_never_passes() {
+        let invalid = Ratio {
+            numerator: 0,
+            denominator: 0,
+        };
+        assert!(!invalid.reached(U256::from(1), U256::from(1)));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::voting_strategies::CensusProof;
+use alloy_primitives::{Address, B256, U256};
+use risc0_steel::ethereum::{EthBlockHeader, EthEvmInput};
+use serde::{Deserialize, Serialize};
+
+/// Input of the voting guest, written by the host as a single value.
+#[derive(Serialize, Deserialize)]
+pub struct VotingInput {
+    /// Must be [`VotingInput::VERSION`], checked by the guest before using any other field.
+    pub version: u32,
+    pub evm_input: EthEvmInput,
+    pub signature: String,
+    pub voter: Address,
+    pub dao: Address,
+    pub proposal_id: U256,
+    /// Vote option as in `IMajorityVoting.VoteOption`, or 0 for a split vote.
+    pub direction: u8,
+    /// Voting power given to the abstain, yes and no options by a split vote, empty otherwise.
+    pub amounts: Vec<U256>,
+    /// Options chosen by a ballot of a multi-option proposal, most preferred first, empty otherwise.
+    pub choices: Vec<u8>,
+    pub balance: U256,
+    pub config_contract: Address,
+    /// Binary form of the proposal's config. The guest checks its canonical JSON form against the
+    /// hash stored by the plugin.
+    pub config: Vec<u8>,
+    pub additional_delegation_data: String,
+    pub census_proofs: Vec<CensusProof>,
+    /// Headers from the snapshot block down to the oldest look-back block.
+    pub ancestors: Vec<EthBlockHeader>,
+    pub lookback_inputs: Vec<EthEvmInput>,
+    /// Inputs of the other chains holding assets, ordered by chain id.
+    pub chain_inputs: Vec<(u64, EthEvmInput)>,
+}
+
+impl VotingInput {
+    pub const VERSION: u32 = 6;
+}
+
+/// Input of the execution guest, written by the host as a single value.
+#[derive(Serialize, Deserialize)]
+pub struct ExecutionInput {
+    /// Must be [`ExecutionInput::VERSION`], checked by the guest before using any other field.
+    pub version: u32,
+    pub evm_input: EthEvmInput,
+    /// Inputs of the other chains holding assets, ordered by chain id.
+    pub chain_inputs: Vec<(u64, EthEvmInput)>,
+    pub dao: Address,
+    pub proposal_id: U256,
+    pub config_contract: Address,
+    /// Binary form of the proposal's config. The guest checks its canonical JSON form against the
+    /// hash stored by the plugin.
+    pub config: Vec<u8>,
+    /// Number of options of a multi-option proposal, or 0 for a yes, no and abstain one. Selects
+    /// the config's execution strategy for the proposal's kind and is committed as is.
+    pub option_count: u8,
+    /// Yes, no and abstain votes, in that order, or the ballots recorded for a multi-option
+    /// proposal.
+    pub tally: Vec<U256>,
+}
+
+impl ExecutionInput {
+    pub const VERSION: u32 = 6;
+}
+
+/// Input of the proposal guest, written by the host as a single value.
+#[derive(Serialize, Deserialize)]
+pub struct ProposalInput {
+    /// Must be [`ProposalInput::VERSION`], checked by the guest before using any other field.
+    pub version: u32,
+    pub evm_input: EthEvmInput,
+    pub proposer: Address,
+    /// Hash of the parameters of the proposal to create, as returned by the plugin's
+    /// `proposalParamsHash`. Committed as is.
+    pub params_hash: B256,
+    pub config_contract: Address,
+    /// Binary form of the plugin's current config. The guest checks its canonical JSON form
+    /// against the hash stored by the plugin.
+    pub config: Vec<u8>,
+    pub additional_delegation_data: String,
+    pub census_proofs: Vec<CensusProof>,
+    /// Headers from the proving block down to the oldest look-back block.
+    pub ancestors: Vec<EthBlockHeader>,
+    pub lookback_inputs: Vec<EthEvmInput>,
+    /// Inputs of the other chains holding assets, ordered by chain id.
+    pub chain_inputs: Vec<(u64, EthEvmInput)>,
+}
+
+impl ProposalInput {
+    pub const VERSION: u32 = 4;
+}
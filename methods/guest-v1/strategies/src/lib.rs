@@ -0,0 +1,482 @@
+pub mod delegation_strategies;
+pub mod execution_strategies;
+pub mod input;
+pub mod voting_strategies;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U512};
+use anyhow::{bail, Result};
+use delegation_strategies::*;
+use execution_strategies::*;
+use revm::primitives::SpecId;
+use risc0_steel::{
+    config::ChainSpec,
+    ethereum::{ETH_MAINNET_CHAIN_SPEC, ETH_SEPOLIA_CHAIN_SPEC},
+    Commitment, EvmEnv,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use voting_strategies::*;
+
+/// `votingProtocolVersion` implemented by the guests built from this crate.
+pub const VOTING_PROTOCOL_VERSION: &str = "1";
+
+pub struct Context {
+    voting_power_strategies: HashMap<String, Box<dyn VotingPowerStrategy>>,
+    delegation_strategies: HashMap<String, Box<dyn DelegationStrategy>>,
+    execution_strategies: HashMap<String, Box<dyn ProtocolExecutionStrategy>>,
+    env: EvmEnv<risc0_steel::StateDb, risc0_steel::ethereum::EthBlockHeader, Commitment>,
+    chain_envs: BTreeMap<u64, GuestEvmEnv>,
+}
+
+pub(crate) type GuestEvmEnv =
+    EvmEnv<risc0_steel::StateDb, risc0_steel::ethereum::EthBlockHeader, Commitment>;
+
+impl Context {
+    pub fn new(env: GuestEvmEnv) -> Self {
+        Self {
+            voting_power_strategies: HashMap::new(),
+            delegation_strategies: HashMap::new(),
+            execution_strategies: HashMap::new(),
+            env,
+            chain_envs: BTreeMap::new(),
+        }
+    }
+
+    pub fn default(env: GuestEvmEnv) -> Self {
+        let mut voting_power_strategies: HashMap<String, Box<dyn VotingPowerStrategy>> =
+            HashMap::new();
+        voting_power_strategies.insert("BalanceOf".to_string(), Box::new(BalanceOf));
+        voting_power_strategies.insert("GetPastVotes".to_string(), Box::new(GetPastVotes));
+        voting_power_strategies.insert("Membership".to_string(), Box::new(Membership));
+        voting_power_strategies.insert(
+            "MerkleCensus".to_string(),
+            Box::new(MerkleCensus::default()),
+        );
+        voting_power_strategies.insert(
+            "MinBalanceOf".to_string(),
+            Box::new(MinBalanceOf::default()),
+        );
+
+        let mut delegation_strategies: HashMap<String, Box<dyn DelegationStrategy>> =
+            HashMap::new();
+        delegation_strategies.insert("SplitDelegation".to_string(), Box::new(SplitDelegation));
+
+        let mut execution_strategies: HashMap<String, Box<dyn ProtocolExecutionStrategy>> =
+            HashMap::new();
+        execution_strategies.insert("MajorityVoting".to_string(), Box::new(MajorityVoting));
+        execution_strategies.insert(
+            "ApprovalVoting".to_string(),
+            Box::new(ApprovalVoting::default()),
+        );
+        execution_strategies.insert(
+            "RankedChoice".to_string(),
+            Box::new(RankedChoice::default()),
+        );
+        execution_strategies.insert("Optimistic".to_string(), Box::new(Optimistic::default()));
+        execution_strategies.insert(
+            "Supermajority".to_string(),
+            Box::new(Supermajority::default()),
+        );
+        execution_strategies.insert(
+            "AbsoluteQuorum".to_string(),
+            Box::new(AbsoluteQuorum::default()),
+        );
+
+        Self {
+            voting_power_strategies,
+            delegation_strategies,
+            execution_strategies,
+            env,
+            chain_envs: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_strategy(&mut self, name: String, protocol_strategy: Box<dyn VotingPowerStrategy>) {
+        self.voting_power_strategies.insert(name, protocol_strategy);
+    }
+
+    pub fn add_execution_strategy(
+        &mut self,
+        name: String,
+        execution_strategy: Box<dyn ProtocolExecutionStrategy>,
+    ) {
+        self.execution_strategies.insert(name, execution_strategy);
+    }
+
+    /// Replaces the execution strategies taking parameters with ones using the config's.
+    pub fn configure_execution_strategies(&mut self, config: &RiscVotingProtocolConfig) {
+        self.add_execution_strategy(
+            "Optimistic".to_string(),
+            Box::new(Optimistic::new(config.veto_threshold.clone())),
+        );
+        self.add_execution_strategy(
+            "Supermajority".to_string(),
+            Box::new(Supermajority::new(
+                config.support_threshold.clone(),
+                config.min_participation.clone(),
+            )),
+        );
+        self.add_execution_strategy(
+            "AbsoluteQuorum".to_string(),
+            Box::new(AbsoluteQuorum::new(config.quorum)),
+        );
+        self.add_execution_strategy(
+            "ApprovalVoting".to_string(),
+            Box::new(ApprovalVoting::new(config.min_participation.clone())),
+        );
+        self.add_execution_strategy(
+            "RankedChoice".to_string(),
+            Box::new(RankedChoice::new(config.min_participation.clone())),
+        );
+    }
+
+    /// Adds the environment used for the assets living on `chain_id`. Assets on chains without
+    /// their own environment are evaluated against the main one. The block must not be newer
+    /// than the snapshot, so balances can't be moved there after the vote was opened.
+    pub fn add_chain_env(&mut self, chain_id: u64, env: GuestEvmEnv) {
+        assert!(
+            env.header().timestamp <= self.env.header().timestamp,
+            "Block of chain {} is newer than the snapshot",
+            chain_id
+        );
+        self.chain_envs.insert(chain_id, env);
+    }
+
+    fn asset_env(&self, asset: &Asset) -> &GuestEvmEnv {
+        self.chain_envs.get(&asset.chain_id).unwrap_or(&self.env)
+    }
+
+    pub fn process_voting_strategy(&self, name: String, account: Address, asset: &Asset) -> U256 {
+        if let Some(protocol_strategy) = self.voting_power_strategies.get(&name) {
+            protocol_strategy.process(self.asset_env(asset), account, asset)
+        } else {
+            panic!("Strategy not found: {}", name);
+        }
+    }
+
+    pub fn process_total_supply(&self, asset: &Asset) -> U256 {
+        if let Some(protocol_strategy) = self
+            .voting_power_strategies
+            .get(&asset.voting_power_strategy)
+        {
+            protocol_strategy.get_supply(self.asset_env(asset), asset)
+        } else {
+            panic!("Strategy not found: {}", &asset.voting_power_strategy);
+        }
+    }
+
+    pub fn process_delegation_strategy(
+        &self,
+        account: Address,
+        asset: &Asset,
+        additional_data: Bytes,
+    ) -> Result<Vec<Delegation>> {
+        if let Some(delegation_strategy) = self
+            .delegation_strategies
+            .get(asset.delegation.strategy.as_str())
+        {
+            delegation_strategy.process(self.asset_env(asset), account, asset, additional_data)
+        } else {
+            bail!("Strategy not found: {}", asset.delegation.strategy);
+        }
+    }
+
+    pub fn process_execution_strategy(
+        &self,
+        name: String,
+        total_supply: U256,
+        tally: &[U256],
+    ) -> bool {
+        if let Some(execution_strategy) = self.execution_strategies.get(&name) {
+            execution_strategy.proof_execution(&self.env, total_supply, tally)
+        } else {
+            panic!("Strategy not found: {}", name);
+        }
+    }
+
+    /// Option chosen by the execution strategy `name`, or 0 if it does not choose among options.
+    pub fn process_execution_winner(&self, name: String, tally: &[U256]) -> u8 {
+        if let Some(execution_strategy) = self.execution_strategies.get(&name) {
+            execution_strategy.winner(tally)
+        } else {
+            panic!("Strategy not found: {}", name);
+        }
+    }
+
+    pub fn block_commitment(&self) -> Commitment {
+        let commitment = self.env.commitment();
+        commitment.clone()
+    }
+
+    /// Commitments of the additional chain environments, ordered by chain id.
+    pub fn chain_commitments(&self) -> Vec<(u64, Commitment)> {
+        self.chain_envs
+            .iter()
+            .map(|(chain_id, env)| (*chain_id, env.commitment().clone()))
+            .collect()
+    }
+}
+
+/// Chain specification used to evaluate the assets living on `chain_id`.
+pub fn chain_spec(chain_id: u64) -> ChainSpec {
+    match chain_id {
+        1 => ETH_MAINNET_CHAIN_SPEC.clone(),
+        11155111 => ETH_SEPOLIA_CHAIN_SPEC.clone(),
+        // L2s such as Optimism, Base and Arbitrum follow the Cancun rules for view calls.
+        chain_id => ChainSpec::new_single(chain_id, SpecId::CANCUN),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationObject {
+    pub contract: Address,
+    pub strategy: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Asset {
+    pub contract: Address,
+    pub chain_id: u64,
+    pub voting_power_strategy: String,
+    pub delegation: DelegationObject,
+    #[serde(default)]
+    pub lookback_blocks: Vec<u64>,
+    /// Membership check of the `Membership` strategy.
+    #[serde(default)]
+    pub member_call: MemberCall,
+    /// Member count of the `Membership` strategy.
+    #[serde(default)]
+    pub member_count_call: MemberCountCall,
+    /// Voting power of each member under the `Membership` strategy.
+    #[serde(default = "default_member_voting_power")]
+    pub member_voting_power: U256,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiscVotingProtocolConfig {
+    pub voting_protocol_version: String,
+    pub assets: Vec<Asset>,
+    /// Execution strategy of the yes, no and abstain proposals.
+    pub execution_strategy: String,
+    /// Execution strategy of the multi-option proposals, which can't be created without one.
+    #[serde(default)]
+    pub multi_option_execution_strategy: Option<String>,
+    /// Share of the total supply voting no that vetoes an `Optimistic` proposal.
+    #[serde(default)]
+    pub veto_threshold: Option<Ratio>,
+    /// Share of the non-abstain votes that must be yes for a `Supermajority` proposal.
+    #[serde(default)]
+    pub support_threshold: Option<Ratio>,
+    /// Share of the total supply that must vote on a `Supermajority` proposal, or cast a ballot
+    /// on an `ApprovalVoting` or `RankedChoice` one.
+    #[serde(default)]
+    pub min_participation: Option<Ratio>,
+    /// Yes votes needed by an `AbsoluteQuorum` proposal.
+    #[serde(default)]
+    pub quorum: Option<U256>,
+}
+
+impl RiscVotingProtocolConfig {
+    /// Execution strategy of the proposals with `option_count` options, 0 meaning a yes, no and
+    /// abstain proposal.
+    pub fn execution_strategy_for(&self, option_count: u8) -> Option<&str> {
+        if option_count == 0 {
+            Some(&self.execution_strategy)
+        } else {
+            self.multi_option_execution_strategy.as_deref()
+        }
+    }
+
+    /// Checks that the config sets valid parameters for `execution_strategy`. The strategies
+    /// reject tallies when theirs are missing, but this tells why.
+    pub fn check_execution_parameters(&self, execution_strategy: &str) -> Result<()> {
+        let valid = |ratio: &Option<Ratio>| ratio.as_ref().is_some_and(Ratio::is_valid);
+        let valid_parameters = match execution_strategy {
+            "Optimistic" => valid(&self.veto_threshold),
+            "Supermajority" => valid(&self.support_threshold) && valid(&self.min_participation),
+            "AbsoluteQuorum" => self.quorum.is_some_and(|quorum| quorum > U256::ZERO),
+            "ApprovalVoting" | "RankedChoice" => valid(&self.min_participation),
+            _ => true,
+        };
+        if !valid_parameters {
+            bail!(
+                "The {} execution strategy is missing parameters in the config",
+                execution_strategy
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Exact fraction used by the execution strategy parameters, such as 2/3.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ratio {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Ratio {
+    /// Whether this is a share between 0 and 1 with a non-zero denominator.
+    pub fn is_valid(&self) -> bool {
+        self.denominator != 0 && self.numerator <= self.denominator
+    }
+
+    /// Whether `value` is at least this ratio of `total`, i.e.
+    /// `value * denominator >= numerator * total`, computed without overflow.
+    pub fn reached(&self, value: U256, total: U256) -> bool {
+        self.denominator != 0
+            && U512::from(value) * U512::from(self.denominator)
+                >= U512::from(self.numerator) * U512::from(total)
+    }
+}
+
+/// Message a voter signs for a vote, as an EIP-191 personal message of 32 bytes. The amounts of
+/// a split vote and the choices of a ballot follow the direction, so single direction votes
+/// (neither) keep their original message.
+pub fn vote_message(
+    chain_id: u64,
+    dao: Address,
+    proposal_id: U256,
+    direction: u8,
+    amounts: &[U256],
+    choices: &[u8],
+    balance: U256,
+) -> B256 {
+    keccak256(
+        [
+            chain_id.to_be_bytes().to_vec(),
+            dao.to_vec(),
+            proposal_id.to_be_bytes_vec(),
+            [direction].to_vec(),
+            amounts
+                .iter()
+                .flat_map(|amount| amount.to_be_bytes_vec())
+                .collect(),
+            choices.to_vec(),
+            balance.to_be_bytes_vec(),
+        ]
+        .concat(),
+    )
+}
+
+impl RiscVotingProtocolConfig {
+    /// Compact binary form of the config, passed to the guests so they don't parse JSON.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("config is always serializable")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Canonical JSON form of the config. The plugin must store this string, as the guests hash
+    /// it to check the binary form they are given.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("config is always serializable")
+    }
+}
+
+/// Hash of a JSON config string, as stored by the plugin in `votingProtocolConfigHash`.
+pub fn config_hash(config: &str) -> B256 {
+    keccak256(config.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(parameters: serde_json::Value) -> RiscVotingProtocolConfig {
+        let mut config = serde_json::json!({
+            "votingProtocolVersion": "1",
+            "assets": [],
+            "executionStrategy": "MajorityVoting",
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(parameters.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn test_execution_parameters_are_checked() {
+        let empty = config(serde_json::json!({}));
+        assert!(empty.check_execution_parameters("MajorityVoting").is_ok());
+        for strategy in [
+            "Optimistic",
+            "Supermajority",
+            "AbsoluteQuorum",
+            "ApprovalVoting",
+            "RankedChoice",
+        ] {
+            assert!(empty.check_execution_parameters(strategy).is_err());
+        }
+
+        let full = config(serde_json::json!({
+            "vetoThreshold": { "numerator": 1, "denominator": 10 },
+            "supportThreshold": { "numerator": 2, "denominator": 3 },
+            "minParticipation": { "numerator": 1, "denominator": 2 },
+            "quorum": "0x3e8",
+        }));
+        for strategy in [
+            "Optimistic",
+            "Supermajority",
+            "AbsoluteQuorum",
+            "ApprovalVoting",
+            "RankedChoice",
+        ] {
+            assert!(full.check_execution_parameters(strategy).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_invalid_ratios_are_rejected() {
+        let zero_denominator = config(serde_json::json!({
+            "vetoThreshold": { "numerator": 0, "denominator": 0 },
+        }));
+        assert!(zero_denominator
+            .check_execution_parameters("Optimistic")
+            .is_err());
+        let above_one = config(serde_json::json!({
+            "minParticipation": { "numerator": 3, "denominator": 2 },
+        }));
+        assert!(above_one
+            .check_execution_parameters("ApprovalVoting")
+            .is_err());
+        let zero_quorum = config(serde_json::json!({ "quorum": "0x0" }));
+        assert!(zero_quorum
+            .check_execution_parameters("AbsoluteQuorum")
+            .is_err());
+    }
+
+    #[test]
+    fn test_binary_config_keeps_the_canonical_json() {
+        let config = config(serde_json::json!({
+            "assets": [{
+                "contract": "0x185Bb1cca668C474214e934028A3e4BB7A5E6525",
+                "chainId": 11155111,
+                "votingPowerStrategy": "BalanceOf",
+                "delegation": {
+                    "contract": "0x32Bb2dB7826cf342743fe80832Fe4DF725879C2D",
+                    "strategy": "SplitDelegation",
+                },
+                "lookbackBlocks": [100],
+            }],
+            "vetoThreshold": { "numerator": 1, "denominator": 10 },
+            "quorum": "0x3e8",
+        }));
+        let canonical = config.to_json();
+
+        let decoded = RiscVotingProtocolConfig::from_bytes(&config.to_bytes()).unwrap();
+        assert_eq!(decoded.to_json(), canonical);
+        assert_eq!(config_hash(&decoded.to_json()), config_hash(&canonical));
+
+        // The canonical form parses back to itself, so the plugin can store it as is.
+        let reparsed = serde_json::from_str::<RiscVotingProtocolConfig>(&canonical).unwrap();
+        assert_eq!(reparsed.to_json(), canonical);
+        assert!(RiscVotingProtocolConfig::from_bytes(&[0xff]).is_err());
+    }
+}
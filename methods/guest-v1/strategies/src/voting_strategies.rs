@@ -0,0 +1,23 @@
+use crate::{Asset, GuestEvmEnv};
+use alloy_primitives::{Address, U256};
+
+pub trait VotingPowerStrategy {
+    fn process(&self, env: &GuestEvmEnv, account: Address, asset: &Asset) -> U256;
+
+    fn get_supply(&self, env: &GuestEvmEnv, asset: &Asset) -> U256;
+}
+
+mod balance_of;
+mod get_past_votes;
+mod membership;
+mod merkle_census;
+mod min_balance_of;
+
+pub use balance_of::BalanceOf;
+pub use get_past_votes::GetPastVotes;
+pub use membership::{
+    default_member_voting_power, membership_supply, MemberCall, MemberCountCall, Membership,
+    MEMBER_VOTING_POWER,
+};
+pub use merkle_census::{census_leaf, verify_census_proof, CensusProof, MerkleCensus};
+pub use min_balance_of::{lookback_block, MinBalanceOf};
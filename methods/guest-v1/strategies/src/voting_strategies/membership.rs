@@ -0,0 +1,164 @@
+use super::VotingPowerStrategy;
+use crate::{Asset, GuestEvmEnv};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use risc0_steel::Contract;
+use serde::{Deserialize, Serialize};
+
+sol! {
+    /// Aragon `IMembership` function signature.
+    interface IMembership {
+        function isMember(address account) external view returns (bool);
+    }
+
+    /// Aragon `Addresslist` function signatures.
+    interface IAddresslist {
+        function isListed(address account) external view returns (bool);
+        function addresslistLength() external view returns (uint256);
+    }
+
+    /// Safe `OwnerManager` function signatures.
+    interface IOwnerManager {
+        function isOwner(address owner) external view returns (bool);
+        function getOwners() external view returns (address[] memory);
+    }
+}
+
+/// Voting power granted to every member when the asset does not set `memberVotingPower`.
+pub const MEMBER_VOTING_POWER: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// Function telling whether an account is a member, named by its Solidity signature in the
+/// asset's `memberCall`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberCall {
+    #[default]
+    #[serde(rename = "isMember(address)")]
+    IsMember,
+    #[serde(rename = "isListed(address)")]
+    IsListed,
+    #[serde(rename = "isOwner(address)")]
+    IsOwner,
+}
+
+/// Function giving the number of members, named by its Solidity signature in the asset's
+/// `memberCountCall`. `getOwners()` counts the returned addresses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberCountCall {
+    #[default]
+    #[serde(rename = "addresslistLength()")]
+    AddresslistLength,
+    #[serde(rename = "getOwners()")]
+    GetOwners,
+}
+
+pub fn default_member_voting_power() -> U256 {
+    MEMBER_VOTING_POWER
+}
+
+/// Voting power of `member_count` members holding `member_voting_power` each, or `None` when it
+/// overflows.
+pub fn membership_supply(member_count: U256, member_voting_power: U256) -> Option<U256> {
+    member_count.checked_mul(member_voting_power)
+}
+
+pub struct Membership;
+impl VotingPowerStrategy for Membership {
+    fn process(&self, env: &GuestEvmEnv, account: Address, asset: &Asset) -> U256 {
+        let membership_contract = Contract::new(asset.contract, env);
+        let is_member = match asset.member_call {
+            MemberCall::IsMember => {
+                let call = IMembership::isMemberCall { account };
+                membership_contract.call_builder(&call).call()._0
+            }
+            MemberCall::IsListed => {
+                let call = IAddresslist::isListedCall { account };
+                membership_contract.call_builder(&call).call()._0
+            }
+            MemberCall::IsOwner => {
+                let call = IOwnerManager::isOwnerCall { owner: account };
+                membership_contract.call_builder(&call).call()._0
+            }
+        };
+        if is_member {
+            asset.member_voting_power
+        } else {
+            U256::ZERO
+        }
+    }
+
+    fn get_supply(&self, env: &GuestEvmEnv, asset: &Asset) -> U256 {
+        let membership_contract = Contract::new(asset.contract, env);
+        let member_count = match asset.member_count_call {
+            MemberCountCall::AddresslistLength => {
+                let call = IAddresslist::addresslistLengthCall {};
+                membership_contract.call_builder(&call).call()._0
+            }
+            MemberCountCall::GetOwners => {
+                let call = IOwnerManager::getOwnersCall {};
+                let owners = membership_contract.call_builder(&call).call();
+                U256::from(owners._0.len())
+            }
+        };
+        membership_supply(member_count, asset.member_voting_power)
+            .expect("Membership supply overflows")
+    }
+}
+
+// Unit tests module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_membership_calls_default_to_addresslist() {
+        let asset: Asset = serde_json::from_str(
+            r#"{
+                "contract": "0x185Bb1cca668C474214e934028A3e4BB7A5E6525",
+                "chainId": 11155111,
+                "votingPowerStrategy": "Membership",
+                "delegation": {
+                    "contract": "0x185Bb1cca668C474214e934028A3e4BB7A5E6525",
+                    "strategy": "SplitDelegation"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(asset.member_call, MemberCall::IsMember);
+        assert_eq!(asset.member_count_call, MemberCountCall::AddresslistLength);
+        assert_eq!(asset.member_voting_power, MEMBER_VOTING_POWER);
+    }
+
+    #[test]
+    fn test_membership_calls_are_configurable() {
+        let asset: Asset = serde_json::from_str(
+            r#"{
+                "contract": "0x185Bb1cca668C474214e934028A3e4BB7A5E6525",
+                "chainId": 11155111,
+                "votingPowerStrategy": "Membership",
+                "delegation": {
+                    "contract": "0x185Bb1cca668C474214e934028A3e4BB7A5E6525",
+                    "strategy": "SplitDelegation"
+                },
+                "memberCall": "isOwner(address)",
+                "memberCountCall": "getOwners()",
+                "memberVotingPower": "1000"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(asset.member_call, MemberCall::IsOwner);
+        assert_eq!(asset.member_count_call, MemberCountCall::GetOwners);
+        assert_eq!(asset.member_voting_power, U256::from(1000));
+
+        let unsupported = serde_json::from_str::<MemberCall>(r#""hasRole(address)""#);
+        assert!(unsupported.is_err());
+    }
+
+    #[test]
+    fn test_membership_supply() {
+        assert_eq!(
+            membership_supply(U256::from(5), U256::from(1000)),
+            Some(U256::from(5000))
+        );
+        assert_eq!(membership_supply(U256::from(2), U256::MAX), None);
+    }
+}
@@ -1,13 +1,12 @@
 #![allow(unused_doc_comments)]
 #![no_main]
 
-use alloy_primitives::{Address, U256};
+use alloy_primitives::U256;
 use alloy_sol_types::{sol, SolValue};
-use risc0_steel::{
-    ethereum::{EthEvmInput, ETH_SEPOLIA_CHAIN_SPEC},
-    Commitment, Contract,
-};
+use journal::{ChainCommitment, ExecutionJournal};
+use risc0_steel::{ethereum::ETH_SEPOLIA_CHAIN_SPEC, Contract};
 use risc0_zkvm::guest::env;
+use strategies::input::ExecutionInput;
 
 risc0_zkvm::guest::entry!(main);
 
@@ -15,45 +14,77 @@ risc0_zkvm::guest::entry!(main);
 /// This parses the Solidity syntax to generate a struct that implements the `SolCall` trait.
 sol! {
     interface ConfigContract {
-        function votingProtocolConfig(uint256 proposal_id) external view returns (string memory);
-    }
-}
-
-/// ABI encodable journal data.
-sol! {
-    struct Journal {
-        Commitment commitment;
-        address config_contract;
-        uint256 proposal_id;
-        uint256[] tally;
+        function votingProtocolConfigHash() external view returns (bytes32);
     }
 }
 
 fn main() {
     // Read the input from the guest environment.
-    println!("Reading input from the guest environment");
-    let input: EthEvmInput = env::read();
-    let _dao: Address = env::read();
-    let proposal_id: U256 = env::read();
-    let tally: [U256; 3] = env::read();
-    let config_contract: Address = env::read();
+    let input: ExecutionInput = env::read();
+    assert_eq!(
+        input.version,
+        ExecutionInput::VERSION,
+        "Unsupported execution input version"
+    );
+    let ExecutionInput {
+        evm_input,
+        chain_inputs,
+        proposal_id,
+        config_contract,
+        config,
+        option_count,
+        tally,
+        ..
+    } = input;
 
     // Converts the input into a `EvmEnv` for execution. The `with_chain_spec` method is used
     // to specify the chain configuration. It checks that the state matches the state root in the
     // header provided in the input.
     let destination_chain_id = &ETH_SEPOLIA_CHAIN_SPEC;
-    let env = input.into_env().with_chain_spec(destination_chain_id);
+    let env = evm_input.into_env().with_chain_spec(destination_chain_id);
 
-    let config_call = ConfigContract::votingProtocolConfigCall { proposal_id };
-    let config_returns = Contract::new(config_contract, &env)
-        .call_builder(&config_call)
+    // The config comes with the input in binary form, which is cheaper than reading the string
+    // from storage through Steel and parsing it. Its canonical JSON form is checked against the
+    // hash stored by the plugin. The proposal is created after its snapshot block, so this is the
+    // plugin's hash, which the proposal copies; the plugin checks the journal against the
+    // proposal's own copy.
+    #[cfg(feature = "cycle-count")]
+    let config_start = env::cycle_count();
+    let config_hash_call = ConfigContract::votingProtocolConfigHashCall {};
+    let config_hash_returns = Contract::new(config_contract, &env)
+        .call_builder(&config_hash_call)
         .call();
-    println!("Config Returns: {:?}", config_returns._0);
+    let config = strategies::RiscVotingProtocolConfig::from_bytes(&config).expect("Invalid config");
+    let config_hash = strategies::config_hash(&config.to_json());
+    assert_eq!(
+        config_hash, config_hash_returns._0,
+        "Config does not match the hash stored by the plugin"
+    );
+    assert_eq!(
+        config.voting_protocol_version,
+        strategies::VOTING_PROTOCOL_VERSION,
+        "Config targets another version of the voting protocol"
+    );
+    #[cfg(feature = "cycle-count")]
+    println!(
+        "Config loaded in {} cycles",
+        env::cycle_count() - config_start
+    );
 
-    let config =
-        serde_json::from_str::<strategies::RiscVotingProtocolConfig>(&config_returns._0).unwrap();
+    let mut strategies_context = strategies::Context::default(env);
+    strategies_context.configure_execution_strategies(&config);
 
-    let strategies_context = strategies::Context::default(env);
+    // Assets on other chains are evaluated against their own environment.
+    let chain_ids: Vec<u64> = chain_inputs.iter().map(|(chain_id, _)| *chain_id).collect();
+    assert!(config.assets.iter().all(|asset| {
+        asset.chain_id == destination_chain_id.chain_id() || chain_ids.contains(&asset.chain_id)
+    }));
+    for (chain_id, input) in chain_inputs {
+        let chain_env = input
+            .into_env()
+            .with_chain_spec(&strategies::chain_spec(chain_id));
+        strategies_context.add_chain_env(chain_id, chain_env);
+    }
 
     // Get the total voting power of the voter across all assets.
     let total_voting_power = config
@@ -62,21 +93,40 @@ fn main() {
         .map(|asset| strategies_context.process_total_supply(asset))
         .sum::<U256>();
 
-    println!("Proposal total voting power: {}", total_voting_power);
-
-    // General settings constraints
+    // General settings constraints. Multi-option proposals are executed with their own strategy,
+    // whose winner must be one of the options; the plugin checks the option count.
+    let execution_strategy = config
+        .execution_strategy_for(option_count)
+        .expect("Config has no execution strategy for multi-option proposals")
+        .to_string();
+    config
+        .check_execution_parameters(&execution_strategy)
+        .expect("Invalid execution strategy parameters");
+    let winner = strategies_context.process_execution_winner(execution_strategy.clone(), &tally);
+    assert!(winner <= option_count, "Winner is not an option");
     assert!(strategies_context.process_execution_strategy(
-        config.execution_strategy,
+        execution_strategy,
         total_voting_power,
-        tally
+        &tally
     ));
 
     // Commit the block hash and number used when deriving `view_call_env` to the journal.
-    let journal = Journal {
+    let journal = ExecutionJournal {
         commitment: strategies_context.block_commitment(),
-        config_contract,
-        proposal_id,
-        tally: tally.to_vec(),
+        configContract: config_contract,
+        configHash: config_hash,
+        proposalId: proposal_id,
+        optionCount: option_count,
+        tally,
+        winner,
+        chainCommitments: strategies_context
+            .chain_commitments()
+            .into_iter()
+            .map(|(chain_id, commitment)| ChainCommitment {
+                chainId: chain_id,
+                commitment,
+            })
+            .collect(),
     };
     env::commit_slice(&journal.abi_encode());
 }
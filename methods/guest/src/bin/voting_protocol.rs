@@ -1,23 +1,18 @@
 #![allow(unused_doc_comments)]
 #![no_main]
 
-use std::str::FromStr;
+use std::{collections::BTreeSet, str::FromStr};
 
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Bytes, U256};
 use alloy_sol_types::{sol, SolValue};
-use hex::FromHex;
-use risc0_steel::{
-    ethereum::{EthEvmInput, ETH_SEPOLIA_CHAIN_SPEC},
-    Commitment, Contract,
-};
+use journal::{ChainCommitment, VotingJournal};
+use risc0_steel::{ethereum::ETH_SEPOLIA_CHAIN_SPEC, Contract};
 use risc0_zkvm::guest::env;
-
-use k256::{
-    ecdsa::{RecoveryId, Signature, VerifyingKey},
-    elliptic_curve::sec1::ToEncodedPoint,
-    PublicKey,
+use strategies::{
+    execution_strategies::MAX_BALLOT_CHOICES,
+    input::VotingInput,
+    voting_strategies::{MerkleCensus, MinBalanceOf},
 };
-use tiny_keccak::{Hasher, Keccak};
 
 risc0_zkvm::guest::entry!(main);
 
@@ -25,137 +20,111 @@ risc0_zkvm::guest::entry!(main);
 /// This parses the Solidity syntax to generate a struct that implements the `SolCall` trait.
 sol! {
     interface ConfigContract {
-        function votingProtocolConfig(uint256 proposal_id) external view returns (string memory);
-    }
-}
-
-/// ABI encodable journal data.
-sol! {
-    struct Journal {
-        Commitment commitment;
-        address config_contract;
-        uint256 proposal_id;
-        address voter;
-        uint256 balance;
-        uint8 direction;
+        function votingProtocolConfigHash() external view returns (bytes32);
     }
 }
 
-fn to_hex_string(bytes: &[u8]) -> String {
-    // Convert each byte to its hexadecimal representation and collect into a single String
-    bytes
-        .iter()
-        .map(|byte| format!("{:02x}", byte))
-        .collect::<_>()
-}
-
-const PREFIX: &str = "\x19Ethereum Signed Message:\n32";
-
-fn keccak256(bytes: &[u8]) -> [u8; 32] {
-    let mut digest = [0u8; 32];
-    let mut hasher = Keccak::v256();
-    hasher.update(bytes);
-    hasher.finalize(&mut digest);
-    digest
-}
-
-/// Converts an Ethereum-convention recovery ID to the k256 RecoveryId type.
-fn into_recovery_id(v: u8) -> Option<RecoveryId> {
-    match v {
-        0 => Some(0),
-        1 => Some(1),
-        27 => Some(0),
-        28 => Some(1),
-        v if v >= 35 => Some((v - 1) % 2),
-        _ => None,
-    }
-    .and_then(RecoveryId::from_byte)
-}
-
-/// Signer address recovery from the (v, r, s) signature components.
-///
-/// This methods exists to replicate the behavior of `ecrecover` within the EVM.
-/// It can only be considered a signature validation is digest is verified to be
-/// the hash of a known message.
-fn ecrecover(v: u8, rs: [u8; 64], digest: [u8; 32]) -> [u8; 20] {
-    let recovery_id = into_recovery_id(v).expect("value for v is invalid");
-    let signature = Signature::from_slice(&rs[..]).expect("signature encoding is invalid");
-    let recovered_pk: PublicKey =
-        VerifyingKey::recover_from_prehash(&digest[..], &signature, recovery_id)
-            .expect("signature is invalid")
-            .into();
-
-    // Calculate the Ethereum address from the k256 public key.
-    let encoded_pk = recovered_pk.to_encoded_point(/* compress = */ false);
-    keccak256(&encoded_pk.as_bytes()[1..])[12..]
-        .try_into()
-        .unwrap()
-}
-
-fn hash_vote(
-    chain_id: u64,
-    dao: Address,
-    proposal_id: U256,
-    direction: u8,
-    balance: U256,
-) -> [u8; 32] {
-    let message_hash = keccak256(
-        &[
-            chain_id.to_be_bytes().to_vec(),
-            dao.to_vec(),
-            proposal_id.to_be_bytes_vec(),
-            [direction].to_vec(),
-            balance.to_be_bytes_vec(),
-        ]
-        .concat(),
-    );
-    let prefixed_message = [PREFIX.as_bytes(), &message_hash].concat();
-    keccak256(&prefixed_message)
-}
-
 fn main() {
     // Read the input from the guest environment.
     println!("Reading input from the guest environment");
-    let input: EthEvmInput = env::read();
-    let signature: String = env::read();
-    let voter: Address = env::read();
-    let dao: Address = env::read();
-    let proposal_id: U256 = env::read();
-    let direction: u8 = env::read();
-    let balance: U256 = env::read();
-    let config_contract: Address = env::read();
-    let additional_delegation_data: String = env::read();
-
-    // 1. Start the signature verification process
-    let digest = hash_vote(
+    let input: VotingInput = env::read();
+    assert_eq!(
+        input.version,
+        VotingInput::VERSION,
+        "Unsupported voting input version"
+    );
+    let VotingInput {
+        evm_input,
+        signature,
+        voter,
+        dao,
+        proposal_id,
+        direction,
+        amounts,
+        choices,
+        balance,
+        config_contract,
+        config,
+        additional_delegation_data,
+        census_proofs,
+        ancestors,
+        lookback_inputs,
+        chain_inputs,
+        ..
+    } = input;
+
+    // 1. Check that the voter signed the vote, as an EIP-191 personal message.
+    let message = strategies::vote_message(
         ETH_SEPOLIA_CHAIN_SPEC.chain_id(),
         dao,
         proposal_id,
         direction,
+        &amounts,
+        &choices,
         balance,
     );
-    let byte_signature = Vec::from_hex(signature).expect("Invalid hex string");
-
-    let v = byte_signature[64];
-    let rs = byte_signature[0..64].try_into().unwrap();
-    let signature_address = ecrecover(v, rs, digest);
+    strategies::check_vote_signer(message, &signature, voter).expect("Invalid vote signature");
 
     // Converts the input into a `EvmEnv` for execution. The `with_chain_spec` method is used
     // to specify the chain configuration. It checks that the state matches the state root in the
     // header provided in the input.
     let destination_chain_id = &ETH_SEPOLIA_CHAIN_SPEC;
-    let env = input.into_env().with_chain_spec(destination_chain_id);
-
-    let config_call = ConfigContract::votingProtocolConfigCall { proposal_id };
-    let config_returns = Contract::new(config_contract, &env)
-        .call_builder(&config_call)
+    let env = evm_input.into_env().with_chain_spec(destination_chain_id);
+
+    // Look-back blocks must be ancestors of the snapshot block.
+    let lookback_envs = lookback_inputs
+        .into_iter()
+        .map(|input| input.into_env().with_chain_spec(destination_chain_id))
+        .collect();
+    let min_balance_of = MinBalanceOf::new(&env, ancestors, lookback_envs);
+    let lookback_commitments = min_balance_of.commitments();
+
+    // The config comes with the input in binary form, which is cheaper than reading the string
+    // from storage through Steel and parsing it. Its canonical JSON form is checked against the
+    // hash stored by the plugin. The proposal is created after its snapshot block, so this is the
+    // plugin's hash, which the proposal copies; the plugin checks the journal against the
+    // proposal's own copy.
+    #[cfg(feature = "cycle-count")]
+    let config_start = env::cycle_count();
+    let config_hash_call = ConfigContract::votingProtocolConfigHashCall {};
+    let config_hash_returns = Contract::new(config_contract, &env)
+        .call_builder(&config_hash_call)
         .call();
-    println!("Config Returns: {:?}", config_returns._0);
+    let config = strategies::RiscVotingProtocolConfig::from_bytes(&config).expect("Invalid config");
+    let config_hash = strategies::config_hash(&config.to_json());
+    assert_eq!(
+        config_hash, config_hash_returns._0,
+        "Config does not match the hash stored by the plugin"
+    );
+    assert_eq!(
+        config.voting_protocol_version,
+        strategies::VOTING_PROTOCOL_VERSION,
+        "Config targets another version of the voting protocol"
+    );
+    #[cfg(feature = "cycle-count")]
+    println!(
+        "Config loaded in {} cycles",
+        env::cycle_count() - config_start
+    );
 
-    let config =
-        serde_json::from_str::<strategies::RiscVotingProtocolConfig>(&config_returns._0).unwrap();
+    let mut strategies_context = strategies::Context::default(env);
+    strategies_context.add_strategy(
+        "MerkleCensus".to_string(),
+        Box::new(MerkleCensus::new(census_proofs)),
+    );
 
-    let strategies_context = strategies::Context::default(env);
+    // Assets on other chains are evaluated against their own environment.
+    let chain_ids: Vec<u64> = chain_inputs.iter().map(|(chain_id, _)| *chain_id).collect();
+    assert!(config.assets.iter().all(|asset| {
+        asset.chain_id == destination_chain_id.chain_id() || chain_ids.contains(&asset.chain_id)
+    }));
+    for (chain_id, input) in chain_inputs {
+        let chain_env = input
+            .into_env()
+            .with_chain_spec(&strategies::chain_spec(chain_id));
+        strategies_context.add_chain_env(chain_id, chain_env);
+    }
+    strategies_context.add_strategy("MinBalanceOf".to_string(), Box::new(min_balance_of));
 
     // Get the total voting power of the voter across all assets.
     let total_voting_power: U256 = config
@@ -191,25 +160,57 @@ fn main() {
     println!("Total voting power: {}", total_voting_power);
 
     // General settings constraints
-    assert!(direction == 1 || direction == 2 || direction == 3);
+    if !choices.is_empty() {
+        // A ballot of a multi-option proposal lists distinct options, numbered from 1. The plugin
+        // checks them against the number of options of the proposal.
+        assert_eq!(direction, 0, "Ballots have no direction");
+        assert!(amounts.is_empty(), "Ballots carry no amounts");
+        assert!(choices.len() <= MAX_BALLOT_CHOICES, "Too many choices");
+        let mut chosen = BTreeSet::new();
+        assert!(
+            choices
+                .iter()
+                .all(|choice| *choice != 0 && chosen.insert(*choice)),
+            "Choices must be distinct options"
+        );
+    } else if direction == 0 {
+        // A split vote must spread exactly the proven voting power over abstain, yes and no.
+        assert_eq!(amounts.len(), 3, "Split votes need one amount per option");
+        let total = amounts
+            .iter()
+            .try_fold(U256::from(0), |acc, amount| acc.checked_add(*amount));
+        assert!(
+            total == Some(balance),
+            "Split vote amounts must add up to the voting power"
+        );
+    } else {
+        assert!(direction == 1 || direction == 2 || direction == 3);
+        assert!(amounts.is_empty(), "Only split votes carry amounts");
+    }
 
     assert!(balance > U256::from(0));
-    println!(
-        "Voter: {:?}, Signature Address: {:?}",
-        voter,
-        to_hex_string(&signature_address)
-    );
-    // assert!(voter.to_string() == to_hex_string(signature_address));
     assert!(balance == total_voting_power);
 
     // Commit the block hash and number used when deriving `view_call_env` to the journal.
-    let journal = Journal {
+    let journal = VotingJournal {
         commitment: strategies_context.block_commitment(),
-        config_contract,
-        proposal_id,
+        configContract: config_contract,
+        configHash: config_hash,
+        proposalId: proposal_id,
         voter,
         balance,
         direction,
+        amounts,
+        choices,
+        lookbackCommitments: lookback_commitments,
+        chainCommitments: strategies_context
+            .chain_commitments()
+            .into_iter()
+            .map(|(chain_id, commitment)| ChainCommitment {
+                chainId: chain_id,
+                commitment,
+            })
+            .collect(),
     };
     env::commit_slice(&journal.abi_encode());
 }
@@ -12,5 +12,59 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Generated crate containing the image ID and ELF binary of the build guest.
+//! Generated crate containing the image IDs and ELF binaries of the build guests.
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+/// Guest images implementing one `votingProtocolVersion`.
+pub struct GuestImages {
+    pub voting_elf: &'static [u8],
+    pub voting_id: [u32; 8],
+    pub execution_elf: &'static [u8],
+    pub execution_id: [u32; 8],
+    pub proposal_elf: &'static [u8],
+    pub proposal_id: [u32; 8],
+}
+
+/// Returns the guest images for a `votingProtocolVersion`. Version 1 is built from the frozen
+/// sources in `guest-v1`, so proposals created under it can still be voted on and executed. The
+/// current guests implement `VOTING_PROTOCOL_VERSION` of `strategies`.
+pub fn guest_images(voting_protocol_version: &str) -> Option<GuestImages> {
+    match voting_protocol_version {
+        "1" => Some(GuestImages {
+            voting_elf: VOTING_PROTOCOL_V1_ELF,
+            voting_id: VOTING_PROTOCOL_V1_ID,
+            execution_elf: EXECUTION_PROTOCOL_V1_ELF,
+            execution_id: EXECUTION_PROTOCOL_V1_ID,
+            proposal_elf: PROPOSAL_PROTOCOL_V1_ELF,
+            proposal_id: PROPOSAL_PROTOCOL_V1_ID,
+        }),
+        "2" => Some(GuestImages {
+            voting_elf: VOTING_PROTOCOL_ELF,
+            voting_id: VOTING_PROTOCOL_ID,
+            execution_elf: EXECUTION_PROTOCOL_ELF,
+            execution_id: EXECUTION_PROTOCOL_ID,
+            proposal_elf: PROPOSAL_PROTOCOL_ELF,
+            proposal_id: PROPOSAL_PROTOCOL_ID,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guest_images_resolve_by_version() {
+        let v1 = guest_images("1").unwrap();
+        let v2 = guest_images("2").unwrap();
+        assert_eq!(v1.voting_id, VOTING_PROTOCOL_V1_ID);
+        assert_eq!(v2.voting_id, VOTING_PROTOCOL_ID);
+        assert_ne!(v1.voting_id, v2.voting_id);
+        assert_ne!(v1.execution_id, v2.execution_id);
+        assert_ne!(v1.proposal_id, v2.proposal_id);
+        assert_ne!(v1.voting_elf, v2.voting_elf);
+        assert!(guest_images("3").is_none());
+        assert!(guest_images("").is_none());
+    }
+}
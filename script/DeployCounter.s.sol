@@ -54,9 +54,17 @@ contract Deploy is Script {
             "risc-voting-protocol-",
             vm.toString(block.timestamp)
         );
-        votingProtocolConfig = vm.readFile(
-            "script/RiscVotingProtocolConfig.txt"
-        );
+        // The guests hash the canonical form of the config, so the plugin stores that form.
+        string[] memory canonicalConfigCommand = new string[](7);
+        canonicalConfigCommand[0] = "cargo";
+        canonicalConfigCommand[1] = "run";
+        canonicalConfigCommand[2] = "-q";
+        canonicalConfigCommand[3] = "--bin=config_hash";
+        canonicalConfigCommand[4] = "--";
+        canonicalConfigCommand[5] = "--canonical";
+        canonicalConfigCommand[6] = "script/RiscVotingProtocolConfig.txt";
+        votingProtocolConfig = string(vm.ffi(canonicalConfigCommand));
+
         token = vm.envAddress("TOKEN");
     }
 
@@ -162,7 +170,8 @@ contract Deploy is Script {
                 votingProtocolConfig: votingProtocolConfig,
                 verifier: address(verifier),
                 votingProtocolImageId: ImageID.VOTING_PROTOCOL_ID,
-                executionProtocolImageId: ImageID.EXECUTION_PROTOCOL_ID
+                executionProtocolImageId: ImageID.EXECUTION_PROTOCOL_ID,
+                proposalProtocolImageId: ImageID.PROPOSAL_PROTOCOL_ID
             });
 
         bytes memory pluginSettingsData = abi.encode(votingSettings);
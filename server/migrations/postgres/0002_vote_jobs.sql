@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS vote_jobs (
+  id serial PRIMARY KEY,
+  state VARCHAR NOT NULL,
+  request TEXT NOT NULL,
+  error VARCHAR,
+  tx_hash VARCHAR,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  started_at TIMESTAMPTZ,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS vote_jobs_state_idx ON vote_jobs (state, id);
@@ -0,0 +1,7 @@
+ALTER TABLE vote_jobs
+  ADD COLUMN IF NOT EXISTS journal TEXT,
+  ADD COLUMN IF NOT EXISTS seal VARCHAR,
+  ADD COLUMN IF NOT EXISTS vote_id INTEGER REFERENCES votes (id) ON DELETE SET NULL,
+  ADD COLUMN IF NOT EXISTS proved_at TIMESTAMPTZ,
+  ADD COLUMN IF NOT EXISTS submitted_at TIMESTAMPTZ,
+  ADD COLUMN IF NOT EXISTS finished_at TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+-- Proposal of each job, with lowercase addresses and the proposal id in decimal, so the votes of
+-- a proposal can be read from its confirmed jobs without truncating the id.
+ALTER TABLE vote_jobs
+  ADD COLUMN IF NOT EXISTS dao VARCHAR,
+  ADD COLUMN IF NOT EXISTS proposal_id VARCHAR,
+  ADD COLUMN IF NOT EXISTS config_contract VARCHAR;
+
+UPDATE vote_jobs SET
+  dao = lower(request::json->>'dao_address'),
+  proposal_id = request::json->>'proposal_id',
+  config_contract = lower(request::json->>'config_contract')
+WHERE dao IS NULL;
+
+CREATE INDEX IF NOT EXISTS vote_jobs_proposal_idx ON vote_jobs (dao, proposal_id);
@@ -0,0 +1,26 @@
+-- Events of the plugins followed by the indexer. `indexed_blocks` holds the hash of the last block
+-- of each indexed range and of each block with events, so a reorg can be found and rewound.
+CREATE TABLE IF NOT EXISTS indexed_blocks (
+  plugin VARCHAR NOT NULL,
+  number BIGINT NOT NULL,
+  hash VARCHAR NOT NULL,
+  PRIMARY KEY (plugin, number)
+);
+
+CREATE TABLE IF NOT EXISTS indexed_events (
+  id serial PRIMARY KEY,
+  plugin VARCHAR NOT NULL,
+  block_number BIGINT NOT NULL,
+  block_hash VARCHAR NOT NULL,
+  tx_hash VARCHAR NOT NULL,
+  log_index BIGINT NOT NULL,
+  kind VARCHAR NOT NULL,
+  proposal_id VARCHAR NOT NULL,
+  account VARCHAR,
+  data TEXT NOT NULL,
+  journal TEXT,
+  UNIQUE (block_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS indexed_events_proposal_idx ON indexed_events (plugin, proposal_id);
+CREATE INDEX IF NOT EXISTS indexed_events_block_idx ON indexed_events (plugin, block_number);
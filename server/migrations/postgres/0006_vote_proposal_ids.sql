@@ -0,0 +1,2 @@
+-- Proposal ids are uint256, so the votes keep them in decimal rather than in a BIGINT.
+ALTER TABLE votes ALTER COLUMN proposal_id TYPE TEXT USING proposal_id::TEXT;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS votes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account TEXT NOT NULL,
+  option INTEGER NOT NULL,
+  proposal_id INTEGER NOT NULL,
+  dao TEXT NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Times are Unix timestamps.
+CREATE TABLE IF NOT EXISTS vote_jobs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  state TEXT NOT NULL,
+  request TEXT NOT NULL,
+  error TEXT,
+  tx_hash TEXT,
+  created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+  started_at INTEGER,
+  updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS vote_jobs_state_idx ON vote_jobs (state, id);
@@ -0,0 +1,6 @@
+ALTER TABLE vote_jobs ADD COLUMN journal TEXT;
+ALTER TABLE vote_jobs ADD COLUMN seal TEXT;
+ALTER TABLE vote_jobs ADD COLUMN vote_id INTEGER REFERENCES votes (id) ON DELETE SET NULL;
+ALTER TABLE vote_jobs ADD COLUMN proved_at INTEGER;
+ALTER TABLE vote_jobs ADD COLUMN submitted_at INTEGER;
+ALTER TABLE vote_jobs ADD COLUMN finished_at INTEGER;
@@ -0,0 +1,13 @@
+-- Proposal of each job, with lowercase addresses and the proposal id in decimal, so the votes of
+-- a proposal can be read from its confirmed jobs without truncating the id.
+ALTER TABLE vote_jobs ADD COLUMN dao TEXT;
+ALTER TABLE vote_jobs ADD COLUMN proposal_id TEXT;
+ALTER TABLE vote_jobs ADD COLUMN config_contract TEXT;
+
+UPDATE vote_jobs SET
+  dao = lower(json_extract(request, '$.dao_address')),
+  proposal_id = json_extract(request, '$.proposal_id'),
+  config_contract = lower(json_extract(request, '$.config_contract'))
+WHERE dao IS NULL;
+
+CREATE INDEX IF NOT EXISTS vote_jobs_proposal_idx ON vote_jobs (dao, proposal_id);
@@ -0,0 +1,26 @@
+-- Events of the plugins followed by the indexer. `indexed_blocks` holds the hash of the last block
+-- of each indexed range and of each block with events, so a reorg can be found and rewound.
+CREATE TABLE IF NOT EXISTS indexed_blocks (
+  plugin TEXT NOT NULL,
+  number INTEGER NOT NULL,
+  hash TEXT NOT NULL,
+  PRIMARY KEY (plugin, number)
+);
+
+CREATE TABLE IF NOT EXISTS indexed_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  plugin TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  block_hash TEXT NOT NULL,
+  tx_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  kind TEXT NOT NULL,
+  proposal_id TEXT NOT NULL,
+  account TEXT,
+  data TEXT NOT NULL,
+  journal TEXT,
+  UNIQUE (block_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS indexed_events_proposal_idx ON indexed_events (plugin, proposal_id);
+CREATE INDEX IF NOT EXISTS indexed_events_block_idx ON indexed_events (plugin, block_number);
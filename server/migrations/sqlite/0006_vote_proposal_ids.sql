@@ -0,0 +1,6 @@
+-- Proposal ids are uint256, so the votes keep them in decimal. The INTEGER column would turn large
+-- ids into lossy REALs, so it is replaced by a TEXT one.
+ALTER TABLE votes ADD COLUMN proposal_id_text TEXT NOT NULL DEFAULT '';
+UPDATE votes SET proposal_id_text = CAST(proposal_id AS TEXT);
+ALTER TABLE votes DROP COLUMN proposal_id;
+ALTER TABLE votes RENAME COLUMN proposal_id_text TO proposal_id;
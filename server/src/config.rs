@@ -0,0 +1,181 @@
+// The server is configured from the TOML file named by `SERVER_CONFIG`, or else from environment
+// variables named after the fields in upper case (`RPC_URL`, `ETH_WALLET_PRIVATE_KEY`, ...).
+
+use std::{collections::BTreeMap, net::SocketAddr, str::FromStr};
+
+use alloy::{
+    network::EthereumWallet, signers::local::PrivateKeySigner, transports::http::reqwest::Url,
+};
+use anyhow::{Context, Result};
+use apps::{ProverOpts, VoteProver};
+use serde::Deserialize;
+use strategies::chain_spec;
+
+/// Names the config file. The environment is used when it is not set.
+const CONFIG_VAR: &str = "SERVER_CONFIG";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Address the standalone server listens on. Shuttle picks its own.
+    #[cfg_attr(feature = "shuttle", allow(dead_code))]
+    #[serde(default = "default_bind_address")]
+    pub bind_address: SocketAddr,
+    /// Database of the standalone server, SQLite for `sqlite:` URLs and Postgres otherwise.
+    /// Shuttle provisions its own Postgres.
+    #[cfg_attr(feature = "shuttle", allow(dead_code))]
+    pub database_url: Option<String>,
+    /// RPC endpoint of the chain the plugin lives on.
+    pub rpc_url: String,
+    /// Chain the plugin lives on. It must match the chain of the guests.
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u64,
+    /// When set, proofs commit to the snapshot's beacon block root instead of its hash.
+    pub beacon_api_url: Option<String>,
+    /// RPC endpoints of the other chains holding assets, by chain id.
+    #[serde(default)]
+    pub chain_rpc_urls: BTreeMap<String, String>,
+    /// Key of the wallet paying for the vote transactions.
+    pub eth_wallet_private_key: String,
+    /// Number of proofs generated at the same time.
+    #[serde(default = "default_prover_workers")]
+    pub prover_workers: usize,
+    /// Kind of the generated proofs. Only Groth16 proofs are accepted by the plugin's verifier.
+    #[serde(default)]
+    pub proof_kind: ProofKind,
+    /// When set, the events of this plugin are indexed into the database.
+    pub plugin_address: Option<String>,
+    /// Block the indexer starts from, usually the one the plugin was deployed in.
+    #[serde(default)]
+    pub indexer_start_block: u64,
+}
+
+/// Receipt kinds the prover can generate.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofKind {
+    #[default]
+    Groth16,
+    Succinct,
+    Composite,
+}
+
+impl ProofKind {
+    fn prover_opts(self) -> ProverOpts {
+        match self {
+            Self::Groth16 => ProverOpts::groth16(),
+            Self::Succinct => ProverOpts::succinct(),
+            Self::Composite => ProverOpts::composite(),
+        }
+    }
+}
+
+impl FromStr for ProofKind {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "groth16" => Ok(Self::Groth16),
+            "succinct" => Ok(Self::Succinct),
+            "composite" => Ok(Self::Composite),
+            _ => anyhow::bail!("expected groth16, succinct or composite"),
+        }
+    }
+}
+
+fn default_bind_address() -> SocketAddr {
+    SocketAddr::from(([0, 0, 0, 0], 8000))
+}
+
+fn default_chain_id() -> u64 {
+    11155111
+}
+
+fn default_prover_workers() -> usize {
+    2
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        match std::env::var(CONFIG_VAR) {
+            Ok(path) => {
+                let file = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read the config file {}", path))?;
+                toml::from_str(&file).with_context(|| format!("invalid config file {}", path))
+            }
+            Err(_) => Self::from_env(),
+        }
+    }
+
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            bind_address: parse_var("BIND_ADDRESS")?.unwrap_or_else(default_bind_address),
+            database_url: var("DATABASE_URL"),
+            rpc_url: var("RPC_URL").context("RPC_URL must be set")?,
+            chain_id: parse_var("CHAIN_ID")?.unwrap_or_else(default_chain_id),
+            beacon_api_url: var("BEACON_API_URL"),
+            // `<CHAIN_ID>=<RPC_URL>` pairs separated by commas.
+            chain_rpc_urls: var("CHAIN_RPC_URLS")
+                .iter()
+                .flat_map(|pairs| pairs.split(','))
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (chain_id, rpc_url) = pair
+                        .split_once('=')
+                        .context("invalid CHAIN_RPC_URLS, expected <CHAIN_ID>=<RPC_URL> pairs")?;
+                    Ok((chain_id.to_string(), rpc_url.to_string()))
+                })
+                .collect::<Result<_>>()?,
+            eth_wallet_private_key: var("ETH_WALLET_PRIVATE_KEY")
+                .context("ETH_WALLET_PRIVATE_KEY must be set")?,
+            prover_workers: parse_var("PROVER_WORKERS")?.unwrap_or_else(default_prover_workers),
+            proof_kind: var("PROOF_KIND")
+                .map(|kind| kind.parse())
+                .transpose()
+                .context("invalid PROOF_KIND")?
+                .unwrap_or_default(),
+            plugin_address: var("PLUGIN_ADDRESS"),
+            indexer_start_block: parse_var("INDEXER_START_BLOCK")?.unwrap_or_default(),
+        })
+    }
+
+    /// Prover of the votes, paid by the configured wallet.
+    pub fn vote_prover(&self) -> Result<VoteProver> {
+        let signer = PrivateKeySigner::from_str(&self.eth_wallet_private_key)
+            .context("invalid eth_wallet_private_key")?;
+        let mut builder =
+            VoteProver::builder(Url::from_str(&self.rpc_url).context("invalid rpc_url")?)
+                .chain_spec(chain_spec(self.chain_id))
+                .prover_opts(self.proof_kind.prover_opts())
+                .wallet(EthereumWallet::from(signer));
+        for (chain_id, rpc_url) in &self.chain_rpc_urls {
+            builder = builder.chain_rpc_url(
+                chain_id
+                    .parse()
+                    .with_context(|| format!("invalid chain id {} in chain_rpc_urls", chain_id))?,
+                Url::from_str(rpc_url)
+                    .with_context(|| format!("invalid RPC URL of chain {}", chain_id))?,
+            );
+        }
+        if let Some(beacon_api_url) = &self.beacon_api_url {
+            builder = builder
+                .beacon_api_url(Url::from_str(beacon_api_url).context("invalid beacon_api_url")?);
+        }
+        Ok(builder.build_vote_prover())
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn parse_var<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    var(name)
+        .map(|value| value.parse())
+        .transpose()
+        .with_context(|| format!("invalid {}", name))
+}
@@ -0,0 +1,631 @@
+// The indexer follows the events of a plugin from a start block to the head of the chain, and
+// stores them with the journal of the proof their transaction carried. Each indexed range records
+// the hash of its last block; when that hash is no longer on the chain, the index is rewound to the
+// last recorded block that still is, and the blocks after it are indexed again.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use alloy::{
+    primitives::{Address, Bytes, TxHash, B256, U256},
+    providers::{Provider, ProviderBuilder, RootProvider},
+    rpc::types::{Filter, Log},
+    sol_types::{SolCall, SolEvent, SolValue},
+    transports::http::{reqwest::Url, Client, Http},
+};
+use anyhow::{Context, Result};
+use apps::IProposals::{BallotCast, VoteCast};
+use async_trait::async_trait;
+use journal::{ExecutionJournal, ProposalJournal, VotingJournal};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    jobs::JournalFields,
+    storage::{IndexedEvent, Storage},
+};
+
+/// How long the indexer waits for new blocks once it reached the head of the chain, or after a
+/// failure.
+const POLL_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Most blocks whose logs are read at once.
+const BLOCK_BATCH: u64 = 1000;
+
+/// Most recorded blocks compared with the chain to find where a reorg forked. Past them, the
+/// plugin is indexed again from the start block.
+const REORG_DEPTH: i64 = 128;
+
+alloy::sol! {
+    /// Events and calls of `RiscVotingProtocolPlugin` read by the indexer, besides the vote events
+    /// of `IProposals`. The proposal events are the ones of OSx's `IProposal`.
+    interface IPlugin {
+        struct Action {
+            address to;
+            uint256 value;
+            bytes data;
+        }
+
+        event ProposalCreated(
+            uint256 indexed proposalId,
+            address indexed creator,
+            uint64 startDate,
+            uint64 endDate,
+            bytes metadata,
+            Action[] actions,
+            uint256 allowFailureMap
+        );
+        event ProposalExecuted(uint256 indexed proposalId);
+        event WinningOptionChosen(uint256 indexed proposalId, uint8 winningOption);
+
+        function createProposalWithProof(
+            bytes journalData,
+            bytes seal,
+            bytes metadata,
+            Action[] actions,
+            uint256 allowFailureMap,
+            uint64 startDate,
+            uint64 endDate
+        ) external returns (uint256 proposalId);
+        function vote(bytes journalData, bytes seal) external;
+        function execute(bytes journalData, bytes seal) external;
+    }
+}
+
+/// Journal fields of a proven execution, as strings like [`JournalFields`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionFields {
+    commitment_id: String,
+    commitment_digest: String,
+    config_contract: String,
+    config_hash: String,
+    proposal_id: String,
+    tally: Vec<String>,
+    winner: u8,
+}
+
+impl ExecutionFields {
+    fn new(journal: &ExecutionJournal) -> Self {
+        Self {
+            commitment_id: journal.commitment.id.to_string(),
+            commitment_digest: journal.commitment.digest.to_string(),
+            config_contract: journal.configContract.to_string(),
+            config_hash: journal.configHash.to_string(),
+            proposal_id: journal.proposalId.to_string(),
+            tally: journal.tally.iter().map(U256::to_string).collect(),
+            winner: journal.winner,
+        }
+    }
+}
+
+/// Journal fields of a proven proposal creation, as strings like [`JournalFields`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposalFields {
+    commitment_id: String,
+    commitment_digest: String,
+    config_contract: String,
+    config_hash: String,
+    proposer: String,
+    voting_power: String,
+}
+
+impl ProposalFields {
+    fn new(journal: &ProposalJournal) -> Self {
+        Self {
+            commitment_id: journal.commitment.id.to_string(),
+            commitment_digest: journal.commitment.digest.to_string(),
+            config_contract: journal.configContract.to_string(),
+            config_hash: journal.configHash.to_string(),
+            proposer: journal.proposer.to_string(),
+            voting_power: journal.votingPower.to_string(),
+        }
+    }
+}
+
+/// Hashes of the blocks of the chain, as the indexer compares them with the recorded ones.
+#[async_trait]
+trait BlockHashes {
+    /// Hash of the block `number`, `None` when the chain does not have it yet.
+    async fn canonical_hash(&self, number: u64) -> Result<Option<B256>>;
+
+    /// Whether the block `number` has the recorded `hash`.
+    async fn on_chain(&self, number: u64, hash: &str) -> Result<bool> {
+        Ok(self
+            .canonical_hash(number)
+            .await?
+            .is_some_and(|block_hash| block_hash.to_string() == hash))
+    }
+}
+
+#[async_trait]
+impl BlockHashes for RootProvider<Http<Client>> {
+    async fn canonical_hash(&self, number: u64) -> Result<Option<B256>> {
+        let block = self.get_block_by_number(number.into(), false).await?;
+        Ok(block.map(|block| block.header.hash))
+    }
+}
+
+/// Rewinds the index of the plugin `key` to the most recent recorded block still on `chain`, or to
+/// before `start_block` when none of the last `REORG_DEPTH` is. Returns the block it kept.
+async fn rewind(
+    storage: &dyn Storage,
+    chain: &impl BlockHashes,
+    key: &str,
+    start_block: u64,
+) -> Result<i64> {
+    let mut fork = i64::try_from(start_block)? - 1;
+    for (number, hash) in storage.indexed_blocks(key, REORG_DEPTH).await? {
+        if chain.on_chain(u64::try_from(number)?, &hash).await? {
+            fork = number;
+            break;
+        }
+    }
+    storage.rewind_index(key, fork).await?;
+    Ok(fork)
+}
+
+pub struct Indexer {
+    storage: Arc<dyn Storage>,
+    provider: RootProvider<Http<Client>>,
+    plugin: Address,
+    /// The plugin's address as stored, in lowercase.
+    key: String,
+    start_block: u64,
+}
+
+impl Indexer {
+    pub fn new(storage: Arc<dyn Storage>, rpc_url: Url, plugin: Address, start_block: u64) -> Self {
+        Self {
+            storage,
+            provider: ProviderBuilder::new().on_http(rpc_url),
+            plugin,
+            key: plugin.to_string().to_lowercase(),
+            start_block,
+        }
+    }
+
+    /// Indexes the plugin in the background for as long as the server runs. Failures are logged
+    /// and the range retried.
+    pub fn start(self) {
+        tokio::spawn(async move {
+            loop {
+                match self.step().await {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(e) => println!("Indexing plugin {} failed: {:#}", self.plugin, e),
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Indexes the next range of blocks, and returns whether the head of the chain is still ahead.
+    async fn step(&self) -> Result<bool> {
+        let from = match self.storage.indexed_blocks(&self.key, 1).await?.first() {
+            Some((number, hash)) => {
+                let number = u64::try_from(*number)?;
+                if !self.provider.on_chain(number, hash).await? {
+                    let fork = rewind(
+                        self.storage.as_ref(),
+                        &self.provider,
+                        &self.key,
+                        self.start_block,
+                    )
+                    .await?;
+                    println!(
+                        "Reorg detected, indexing plugin {} again from block {}",
+                        self.plugin,
+                        fork + 1
+                    );
+                    return Ok(true);
+                }
+                number + 1
+            }
+            None => self.start_block,
+        };
+        let head = self.provider.get_block_number().await?;
+        if from > head {
+            return Ok(false);
+        }
+        let to = head.min(from + BLOCK_BATCH - 1);
+        let to_hash = self
+            .provider
+            .canonical_hash(to)
+            .await?
+            .with_context(|| format!("block {} not found", to))?;
+
+        let filter = Filter::new()
+            .address(self.plugin)
+            .from_block(from)
+            .to_block(to);
+        let logs = self.provider.get_logs(&filter).await?;
+        // Logs read during a reorg may come from both branches, so the range is read again.
+        if self.provider.canonical_hash(to).await? != Some(to_hash) {
+            return Ok(true);
+        }
+
+        let mut blocks = BTreeMap::from([(i64::try_from(to)?, to_hash.to_string())]);
+        let mut inputs = HashMap::new();
+        let mut events = Vec::new();
+        for log in &logs {
+            if let Some(event) = self.event(log, &mut inputs).await? {
+                blocks.insert(event.block_number, event.block_hash.clone());
+                events.push(event);
+            }
+        }
+        let blocks: Vec<_> = blocks.into_iter().collect();
+        self.storage
+            .insert_indexed(&self.key, &blocks, &events)
+            .await?;
+        if !events.is_empty() {
+            println!(
+                "Indexed {} events of plugin {} up to block {}",
+                events.len(),
+                self.plugin,
+                to
+            );
+        }
+        Ok(to < head)
+    }
+
+    /// Decodes a log of the plugin, with the journal of its transaction when the transaction
+    /// called the plugin directly. `inputs` keeps the input of each transaction already read.
+    /// Returns `None` for the events that are not indexed. Logs and journals that can't be decoded
+    /// are logged and skipped, since reading them again would not fix them and the index would
+    /// stop there.
+    async fn event(
+        &self,
+        log: &Log,
+        inputs: &mut HashMap<TxHash, Option<Bytes>>,
+    ) -> Result<Option<IndexedEvent>> {
+        let (kind, proposal_id, account, data) = match decode_log(log) {
+            Ok(Some(decoded)) => decoded,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                println!(
+                    "Skipping an invalid log of plugin {} in transaction {:?}: {:#}",
+                    self.plugin, log.transaction_hash, e
+                );
+                return Ok(None);
+            }
+        };
+
+        let block_number = log.block_number.context("log without a block")?;
+        let block_hash = log.block_hash.context("log without a block")?;
+        let tx_hash = log.transaction_hash.context("log without a transaction")?;
+        let log_index = log.log_index.context("log without an index")?;
+        if !inputs.contains_key(&tx_hash) {
+            let tx = self
+                .provider
+                .get_transaction_by_hash(tx_hash)
+                .await?
+                .with_context(|| format!("transaction {} not found", tx_hash))?;
+            inputs.insert(tx_hash, (tx.to == Some(self.plugin)).then_some(tx.input));
+        }
+        let journal = match &inputs[&tx_hash] {
+            Some(input) => journal_fields(input).unwrap_or_else(|e| {
+                println!(
+                    "Skipping the invalid journal of transaction {}: {:#}",
+                    tx_hash, e
+                );
+                None
+            }),
+            None => None,
+        };
+
+        Ok(Some(IndexedEvent {
+            block_number: i64::try_from(block_number)?,
+            block_hash: block_hash.to_string(),
+            tx_hash: tx_hash.to_string(),
+            log_index: i64::try_from(log_index)?,
+            kind,
+            proposal_id: proposal_id.to_string(),
+            account: account.map(|account| account.to_string().to_lowercase()),
+            data: data.to_string(),
+            journal,
+        }))
+    }
+}
+
+/// An indexed event: its kind, proposal id, account and fields as JSON.
+type DecodedLog = (&'static str, U256, Option<Address>, Value);
+
+/// Decodes a log of the plugin. Returns `None` for the events that are not indexed.
+fn decode_log(log: &Log) -> Result<Option<DecodedLog>> {
+    let topic0 = log.topic0().copied();
+    let decoded = if topic0 == Some(IPlugin::ProposalCreated::SIGNATURE_HASH) {
+        let event = log.log_decode::<IPlugin::ProposalCreated>()?.inner.data;
+        let actions: Vec<Value> = event
+            .actions
+            .iter()
+            .map(|action| {
+                json!({
+                    "to": action.to.to_string(),
+                    "value": action.value.to_string(),
+                    "data": action.data.to_string(),
+                })
+            })
+            .collect();
+        let data = json!({
+            "startDate": event.startDate,
+            "endDate": event.endDate,
+            "metadata": event.metadata.to_string(),
+            "actions": actions,
+            "allowFailureMap": event.allowFailureMap.to_string(),
+        });
+        (
+            "proposal_created",
+            event.proposalId,
+            Some(event.creator),
+            data,
+        )
+    } else if topic0 == Some(VoteCast::SIGNATURE_HASH) {
+        let event = log.log_decode::<VoteCast>()?.inner.data;
+        let data = json!({
+            "voteOption": event.voteOption,
+            "votingPower": event.votingPower.to_string(),
+        });
+        ("vote_cast", event.proposalId, Some(event.voter), data)
+    } else if topic0 == Some(BallotCast::SIGNATURE_HASH) {
+        let event = log.log_decode::<BallotCast>()?.inner.data;
+        let data = json!({
+            "choices": event.choices,
+            "votingPower": event.votingPower.to_string(),
+        });
+        ("ballot_cast", event.proposalId, Some(event.voter), data)
+    } else if topic0 == Some(IPlugin::WinningOptionChosen::SIGNATURE_HASH) {
+        let event = log.log_decode::<IPlugin::WinningOptionChosen>()?.inner.data;
+        let data = json!({ "winningOption": event.winningOption });
+        ("winning_option_chosen", event.proposalId, None, data)
+    } else if topic0 == Some(IPlugin::ProposalExecuted::SIGNATURE_HASH) {
+        let event = log.log_decode::<IPlugin::ProposalExecuted>()?.inner.data;
+        ("proposal_executed", event.proposalId, None, json!({}))
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(decoded))
+}
+
+/// Journal fields, as JSON, of the proof passed to a call of the plugin. Returns `None` for calls
+/// without a proof.
+fn journal_fields(input: &[u8]) -> Result<Option<String>> {
+    let fields = if let Ok(call) = IPlugin::voteCall::abi_decode(input, true) {
+        let journal = VotingJournal::abi_decode(&call.journalData, true)?;
+        serde_json::to_string(&JournalFields::new(&journal))?
+    } else if let Ok(call) = IPlugin::executeCall::abi_decode(input, true) {
+        let journal = ExecutionJournal::abi_decode(&call.journalData, true)?;
+        serde_json::to_string(&ExecutionFields::new(&journal))?
+    } else if let Ok(call) = IPlugin::createProposalWithProofCall::abi_decode(input, true) {
+        let journal = ProposalJournal::abi_decode(&call.journalData, true)?;
+        serde_json::to_string(&ProposalFields::new(&journal))?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        network::{EthereumWallet, TransactionBuilder},
+        node_bindings::Anvil,
+        rpc::types::TransactionRequest,
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+    use crate::storage::SqliteStorage;
+
+    /// Creation code of a contract that emits `VoteCast(proposalId, msg.sender, voteOption,
+    /// votingPower)` for any call with the ABI encoded `(proposalId, voteOption, votingPower)`.
+    fn vote_emitter() -> Bytes {
+        let mut runtime = vec![
+            0x60, 0x40, // PUSH1 64
+            0x60, 0x20, // PUSH1 32
+            0x60, 0x00, // PUSH1 0
+            0x37, // CALLDATACOPY: voteOption and votingPower as the data
+            0x33, // CALLER: voter
+            0x60, 0x00, // PUSH1 0
+            0x35, // CALLDATALOAD: proposalId
+            0x7f, // PUSH32 the event signature
+        ];
+        runtime.extend_from_slice(VoteCast::SIGNATURE_HASH.as_slice());
+        runtime.extend_from_slice(&[
+            0x60, 0x40, // PUSH1 64
+            0x60, 0x00, // PUSH1 0
+            0xa3, // LOG3
+            0x00, // STOP
+        ]);
+        let length = u8::try_from(runtime.len()).unwrap();
+        let mut code = vec![
+            0x60, length, // PUSH1 the runtime length
+            0x80,   // DUP1
+            0x60, 0x0b, // PUSH1 11, where the runtime starts
+            0x60, 0x00, // PUSH1 0
+            0x39, // CODECOPY
+            0x60, 0x00, // PUSH1 0
+            0xf3, // RETURN
+        ];
+        code.extend(runtime);
+        code.into()
+    }
+
+    async fn index(indexer: &Indexer) {
+        while indexer.step().await.unwrap() {}
+    }
+
+    /// A chain with the blocks of a map.
+    struct Blocks(BTreeMap<u64, B256>);
+
+    #[async_trait]
+    impl BlockHashes for Blocks {
+        async fn canonical_hash(&self, number: u64) -> Result<Option<B256>> {
+            Ok(self.0.get(&number).copied())
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_rewind_keeps_the_blocks_still_on_chain() {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let key = "0x0000000000000000000000000000000000000001";
+        let hash = |byte: u8| B256::repeat_byte(byte);
+        let recorded: Vec<(i64, String)> = [10, 20, 30]
+            .into_iter()
+            .map(|number| (i64::from(number), hash(number).to_string()))
+            .collect();
+        storage.insert_indexed(key, &recorded, &[]).await.unwrap();
+
+        // Block 30 was reorged out, and the chain has not reached the height of block 40 yet.
+        let chain = Blocks(BTreeMap::from([
+            (10, hash(10)),
+            (20, hash(20)),
+            (30, hash(31)),
+        ]));
+        assert_eq!(rewind(storage.as_ref(), &chain, key, 5).await.unwrap(), 20);
+        assert_eq!(
+            storage.indexed_blocks(key, 10).await.unwrap(),
+            vec![recorded[1].clone(), recorded[0].clone()]
+        );
+
+        // Past every recorded block, the plugin is indexed again from the start block.
+        let chain = Blocks(BTreeMap::from([(10, hash(11)), (20, hash(21))]));
+        assert_eq!(rewind(storage.as_ref(), &chain, key, 5).await.unwrap(), 4);
+        assert!(storage.indexed_blocks(key, 10).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_logs_are_skipped() {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let plugin = Address::repeat_byte(1);
+        // No node is reached, as the log is skipped before its transaction is read.
+        let indexer = Indexer::new(
+            storage,
+            Url::parse("http://127.0.0.1:1").unwrap(),
+            plugin,
+            0,
+        );
+        // A `VoteCast` without the voter topic nor its data.
+        let log = Log {
+            inner: alloy::primitives::Log::new_unchecked(
+                plugin,
+                vec![VoteCast::SIGNATURE_HASH, B256::with_last_byte(1)],
+                Bytes::new(),
+            ),
+            block_number: Some(1),
+            block_hash: Some(B256::repeat_byte(2)),
+            transaction_hash: Some(TxHash::repeat_byte(3)),
+            log_index: Some(0),
+            ..Default::default()
+        };
+
+        assert!(decode_log(&log).is_err());
+        let event = indexer.event(&log, &mut HashMap::new()).await.unwrap();
+        assert!(event.is_none());
+    }
+
+    #[test]
+    fn test_invalid_journals_fail_to_decode() {
+        let input = IPlugin::voteCall {
+            journalData: Bytes::from_static(b"not a journal"),
+            seal: Bytes::new(),
+        }
+        .abi_encode();
+        assert!(journal_fields(&input).is_err());
+        assert_eq!(journal_fields(b"").unwrap(), None);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs anvil"]
+    async fn test_indexes_votes_and_rewinds_reorgs() {
+        let anvil = Anvil::new().spawn();
+        let voter = anvil.addresses()[0];
+        let signer = PrivateKeySigner::from(anvil.keys()[0].clone());
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(anvil.endpoint_url());
+        let vote = |plugin: Address, option: u8| {
+            let input = (U256::from(1), U256::from(option), U256::from(5)).abi_encode_params();
+            TransactionRequest::default()
+                .with_to(plugin)
+                .with_input(input)
+        };
+
+        let deploy = TransactionRequest::default().with_deploy_code(vote_emitter());
+        let receipt = provider
+            .send_transaction(deploy)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        let plugin = receipt.contract_address.unwrap();
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let indexer = Indexer::new(storage.clone(), anvil.endpoint_url(), plugin, 0);
+        let key = plugin.to_string().to_lowercase();
+        index(&indexer).await;
+        let deployed = storage.indexed_blocks(&key, 1).await.unwrap();
+
+        let snapshot: U256 = provider
+            .raw_request("evm_snapshot".into(), json!([]))
+            .await
+            .unwrap();
+        provider
+            .send_transaction(vote(plugin, 2))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        index(&indexer).await;
+
+        let casts = storage.indexed_casts(&key, "1").await.unwrap();
+        assert_eq!(casts.len(), 1);
+        assert_eq!(casts[0].kind, "vote_cast");
+        assert_eq!(
+            casts[0].account.as_deref(),
+            Some(voter.to_string().to_lowercase().as_str())
+        );
+        let data: Value = serde_json::from_str(&casts[0].data).unwrap();
+        assert_eq!(data, json!({ "voteOption": 2, "votingPower": "5" }));
+
+        // The vote is replaced by another one in a block of the same height.
+        let reverted: bool = provider
+            .raw_request("evm_revert".into(), json!([snapshot]))
+            .await
+            .unwrap();
+        assert!(reverted);
+        let receipt = provider
+            .send_transaction(vote(plugin, 3))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        index(&indexer).await;
+
+        let casts = storage.indexed_casts(&key, "1").await.unwrap();
+        assert_eq!(casts.len(), 1);
+        assert_eq!(casts[0].tx_hash, receipt.transaction_hash.to_string());
+        let data: Value = serde_json::from_str(&casts[0].data).unwrap();
+        assert_eq!(data["voteOption"], 3);
+        // The rewind stopped at the last block both branches share.
+        let blocks = storage.indexed_blocks(&key, 10).await.unwrap();
+        assert_eq!(blocks[1..], deployed[..]);
+        assert_eq!(
+            blocks[0],
+            (
+                i64::try_from(receipt.block_number.unwrap()).unwrap(),
+                receipt.block_hash.unwrap().to_string()
+            )
+        );
+    }
+}
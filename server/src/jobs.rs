@@ -0,0 +1,338 @@
+// Proof requests are persisted as jobs and processed by a fixed pool of workers calling the
+// proving library, so the request handler returns as soon as the job is stored.
+
+use std::{sync::Arc, thread, time::Duration};
+
+use alloy::primitives::U256;
+use anyhow::{ensure, Result};
+use apps::VoteProver;
+use journal::VotingJournal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use tokio::sync::{broadcast, Notify};
+
+use crate::{
+    storage::{Storage, VoteNew},
+    VotingParams,
+};
+
+/// How long an idle worker waits before looking for jobs again, in case it missed a wake-up.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of state transitions kept for subscribers that fall behind.
+const EVENT_CAPACITY: usize = 256;
+
+/// States of a job, in order. A job can fail from any state before `Confirmed`. A claimed job
+/// stays `Queued` while it is preflighted. The preflight is only kept in memory, so `Preflighted`
+/// and `Proving` jobs are queued again after a restart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    Preflighted,
+    Proving,
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+impl JobState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Preflighted => "preflighted",
+            JobState::Proving => "proving",
+            JobState::Submitted => "submitted",
+            JobState::Confirmed => "confirmed",
+            JobState::Failed => "failed",
+        }
+    }
+
+    /// Whether the job will not change anymore.
+    pub fn is_final(state: &str) -> bool {
+        state == JobState::Confirmed.as_str() || state == JobState::Failed.as_str()
+    }
+}
+
+/// A state transition of a job, pushed to the subscribers of its events.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEvent {
+    pub id: i32,
+    pub state: String,
+    pub error: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+/// A job as stored, with its times as Unix timestamps.
+#[derive(FromRow)]
+pub struct JobRow {
+    pub id: i32,
+    pub state: String,
+    pub error: Option<String>,
+    pub tx_hash: Option<String>,
+    pub journal: Option<String>,
+    pub seal: Option<String>,
+    pub vote_id: Option<i32>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub proved_at: Option<i64>,
+    pub submitted_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+impl JobRow {
+    pub fn event(&self) -> JobEvent {
+        JobEvent {
+            id: self.id,
+            state: self.state.clone(),
+            error: self.error.clone(),
+            tx_hash: self.tx_hash.clone(),
+        }
+    }
+}
+
+/// Journal fields of a proven vote, as strings so they keep their full precision in JSON.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalFields {
+    pub commitment_id: String,
+    pub commitment_digest: String,
+    pub config_contract: String,
+    pub config_hash: String,
+    pub proposal_id: String,
+    pub voter: String,
+    pub balance: String,
+    pub direction: u8,
+    pub amounts: Vec<String>,
+    pub choices: Vec<u8>,
+}
+
+impl JournalFields {
+    pub fn new(journal: &VotingJournal) -> Self {
+        Self {
+            commitment_id: journal.commitment.id.to_string(),
+            commitment_digest: journal.commitment.digest.to_string(),
+            config_contract: journal.configContract.to_string(),
+            config_hash: journal.configHash.to_string(),
+            proposal_id: journal.proposalId.to_string(),
+            voter: journal.voter.to_string(),
+            balance: journal.balance.to_string(),
+            direction: journal.direction,
+            amounts: journal.amounts.iter().map(U256::to_string).collect(),
+            choices: journal.choices.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JobQueue {
+    storage: Arc<dyn Storage>,
+    prover: VoteProver,
+    notify: Arc<Notify>,
+    events: broadcast::Sender<JobEvent>,
+}
+
+impl JobQueue {
+    pub fn new(storage: Arc<dyn Storage>, prover: VoteProver) -> Self {
+        Self {
+            storage,
+            prover,
+            notify: Arc::new(Notify::new()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Receives the state transitions of all jobs from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<JobEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn job(&self, id: i32) -> Result<Option<JobRow>> {
+        Ok(self.storage.job(id).await?)
+    }
+
+    /// Stores a proof request and wakes up a worker. Returns the id of the job.
+    pub async fn enqueue(&self, params: &VotingParams) -> Result<i32> {
+        // The proposal is stored normalized, so its votes can be looked up.
+        let request = params.vote_request()?;
+        let id = self
+            .storage
+            .insert_job(
+                &serde_json::to_string(params)?,
+                &request.dao.to_string().to_lowercase(),
+                &request.proposal_id.to_string(),
+                &request.config_contract.to_string().to_lowercase(),
+            )
+            .await?;
+        self.notify.notify_one();
+        Ok(id)
+    }
+
+    /// Recovers the jobs interrupted by a restart and starts `workers` workers.
+    ///
+    /// Jobs that had not been sent yet, preflighted or not, are queued again. Sent ones are failed,
+    /// as their transaction may or may not have landed.
+    pub async fn start(&self, workers: usize) -> Result<()> {
+        self.storage
+            .recover_jobs("interrupted before confirmation, check the transaction")
+            .await?;
+
+        // The preflight futures are not `Send`, so each worker drives its own single-threaded
+        // runtime.
+        for worker in 0..workers {
+            let queue = self.clone();
+            thread::Builder::new()
+                .name(format!("prover-{}", worker))
+                .spawn(move || {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("Failed to build the worker runtime")
+                        .block_on(queue.work())
+                })?;
+        }
+        Ok(())
+    }
+
+    async fn work(&self) {
+        loop {
+            match self.run_next().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    let _ = tokio::time::timeout(IDLE_POLL_INTERVAL, self.notify.notified()).await;
+                }
+                Err(e) => {
+                    println!("Failed to claim a job: {}", e);
+                    tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// Claims a job and processes it, recording its failure. Returns whether there was one.
+    async fn run_next(&self) -> Result<bool> {
+        let Some((id, request)) = self.claim().await? else {
+            return Ok(false);
+        };
+        if let Err(e) = self.process(id, &request).await {
+            println!("Job {} failed: {:#}", id, e);
+            if let Err(e) = self.fail(id, &e).await {
+                println!("Failed to record the failure of job {}: {}", id, e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Takes the oldest queued job no other worker started.
+    async fn claim(&self) -> Result<Option<(i32, String)>> {
+        Ok(self.storage.claim_job().await?)
+    }
+
+    async fn process(&self, id: i32, request: &str) -> Result<()> {
+        let params: VotingParams = serde_json::from_str(request)?;
+        let request = params.vote_request()?;
+
+        let preflight = self.prover.preflight(&request).await?;
+        if let Some(balance) = params.balance()? {
+            ensure!(
+                balance == preflight.quote.balance,
+                "balance {} does not match the voting power {}",
+                balance,
+                preflight.quote.balance
+            );
+        }
+        self.set_state(id, JobState::Preflighted).await?;
+
+        self.set_state(id, JobState::Proving).await?;
+        let signature = params.voter_signature.trim_start_matches("0x").to_string();
+        let proof = self.prover.prove(preflight, request, signature).await?;
+        self.storage
+            .set_job_proof(
+                id,
+                &serde_json::to_string(&JournalFields::new(&proof.journal))?,
+                &proof.seal.to_string(),
+            )
+            .await?;
+
+        let tx_hash = self.prover.send(&proof).await?;
+        self.storage
+            .set_job_submitted(id, &tx_hash.to_string())
+            .await?;
+        self.publish(id).await?;
+
+        self.prover.confirm(tx_hash).await?;
+
+        // Confirmed votes are also listed under `/votes`.
+        let vote = VoteNew {
+            account: proof.journal.voter.to_string(),
+            option: i16::from(proof.journal.direction),
+            proposal_id: proof.journal.proposalId,
+            dao: params.dao_address.clone(),
+        };
+        self.storage.set_job_confirmed(id, Some(&vote)).await?;
+        self.publish(id).await?;
+        println!("Job {} confirmed: {}", id, tx_hash);
+        Ok(())
+    }
+
+    async fn set_state(&self, id: i32, state: JobState) -> Result<()> {
+        self.storage.set_job_state(id, state).await?;
+        self.publish(id).await
+    }
+
+    /// Pushes the current state of a job to its subscribers.
+    async fn publish(&self, id: i32) -> Result<()> {
+        if let Some(job) = self.job(id).await? {
+            // Sending only fails when nobody listens.
+            let _ = self.events.send(job.event());
+        }
+        Ok(())
+    }
+
+    async fn fail(&self, id: i32, error: &anyhow::Error) -> Result<()> {
+        self.storage
+            .set_job_failed(id, &format!("{:#}", error))
+            .await?;
+        self.publish(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::transports::http::reqwest::Url;
+    use apps::ProverBuilder;
+
+    use super::*;
+    use crate::storage::SqliteStorage;
+
+    /// A queue over an in-memory database whose prover can't reach any node.
+    async fn queue() -> (JobQueue, Arc<dyn Storage>) {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let prover =
+            ProverBuilder::new(Url::parse("http://127.0.0.1:1").unwrap()).build_vote_prover();
+        (JobQueue::new(storage.clone(), prover), storage)
+    }
+
+    #[actix_web::test]
+    async fn test_failed_job_records_its_error() {
+        let (queue, storage) = queue().await;
+        let id = storage
+            .insert_job("not a request", "0x01", "1", "0x02")
+            .await
+            .unwrap();
+        let mut events = queue.subscribe();
+
+        assert!(queue.run_next().await.unwrap());
+        assert!(!queue.run_next().await.unwrap());
+
+        let job = queue.job(id).await.unwrap().unwrap();
+        assert_eq!(job.state, JobState::Failed.as_str());
+        assert!(job.error.is_some());
+        assert!(job.finished_at.is_some());
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.id, id);
+        assert_eq!(event.state, JobState::Failed.as_str());
+        assert_eq!(event.error, job.error);
+    }
+}
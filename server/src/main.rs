@@ -5,19 +5,40 @@ use actix_web::{
     middleware::Logger,
     post,
     web::{self, Json, ServiceConfig},
-    Result,
+    HttpResponse, Result,
 };
-use std::process::Command;
+use alloy::{
+    primitives::{Address, Signature, U256},
+    transports::http::reqwest::Url,
+};
+use anyhow::Context;
+use apps::{
+    vote::{check_request, recover_signer, VoteRequest},
+    VoteProver,
+};
+use config::Config;
+use futures::{future, stream, StreamExt};
+use indexer::Indexer;
+use jobs::{JobQueue, JobRow, JobState};
+use proposals::{proposal_view, Index, NotIndexed, ProposalView};
+use std::{str::FromStr, sync::Arc};
+use storage::{Storage, Vote, VoteNew};
+use strategies::voting_strategies::CensusProof;
 
 use serde::{Deserialize, Serialize};
-use shuttle_actix_web::ShuttleActixWeb;
-use sqlx::{FromRow, PgPool};
+use tokio::sync::broadcast::error::RecvError;
+
+mod config;
+mod indexer;
+mod jobs;
+mod proposals;
+mod storage;
 
 #[get("/{id}")]
 async fn retrieve_vote(path: web::Path<i32>, state: web::Data<AppState>) -> Result<Json<Vote>> {
-    let vote = sqlx::query_as("SELECT * FROM votes WHERE id = $1")
-        .bind(*path)
-        .fetch_one(&state.pool)
+    let vote = state
+        .storage
+        .vote(*path)
         .await
         .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
 
@@ -26,8 +47,9 @@ async fn retrieve_vote(path: web::Path<i32>, state: web::Data<AppState>) -> Resu
 
 #[get("")]
 async fn retrieve_all_votes(state: web::Data<AppState>) -> Result<Json<Vec<Vote>>> {
-    let votes = sqlx::query_as("SELECT * FROM votes ORDER BY id DESC")
-        .fetch_all(&state.pool)
+    let votes = state
+        .storage
+        .votes()
         .await
         .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
 
@@ -36,12 +58,9 @@ async fn retrieve_all_votes(state: web::Data<AppState>) -> Result<Json<Vec<Vote>
 
 #[post("")]
 async fn add_vote(vote: web::Json<VoteNew>, state: web::Data<AppState>) -> Result<Json<Vote>> {
-    let vote = sqlx::query_as("INSERT INTO votes(account, option, proposal_id, dao) VALUES ($1, $2, $3, $4) RETURNING id, account, option, proposal_id, dao")
-        .bind(&vote.account)
-        .bind(&vote.option)
-        .bind(&vote.proposal_id)
-        .bind(&vote.dao)
-        .fetch_one(&state.pool)
+    let vote = state
+        .storage
+        .add_vote(&vote)
         .await
         .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
 
@@ -50,134 +69,618 @@ async fn add_vote(vote: web::Json<VoteNew>, state: web::Data<AppState>) -> Resul
 
 #[delete("/{id}")]
 async fn delete_vote(path: web::Path<i32>, state: web::Data<AppState>) -> Result<Json<()>> {
-    sqlx::query("DELETE FROM votes WHERE id = $1")
-        .bind(*path)
-        .execute(&state.pool)
-        .await
-        .unwrap();
+    state.storage.delete_vote(*path).await.unwrap();
 
     Ok(Json(()))
 }
 
 #[post("/vote")]
-async fn proof_vote(Json(payload): Json<VotingParams>) -> Result<Json<()>> {
-    let voter_signature = &payload.voter_signature[2..];
+async fn proof_vote(
+    Json(payload): Json<VotingParams>,
+    state: web::Data<AppState>,
+) -> Result<Json<JobCreated>> {
     println!("Request received");
-    println!("Blocknumber: {}", payload.block_number);
-    println!("Voter Signature: {}", voter_signature);
     println!("Voter: {}", payload.voter);
     println!("DAO Address: {}", payload.dao_address);
     println!("Proposal Id: {}", payload.proposal_id);
-    println!("Direction: {}", payload.direction);
-    println!("Balance: {}", payload.balance);
-    println!("Config Contract: {}", payload.config_contract);
     println!("Token Address: {}", payload.token_address);
-    println!(
-        "Additional Delegation Data: {:?}",
-        payload.additional_delegation_data
-    );
 
-    let output = Command::new("./publisher")
-        // .current_dir("../target/release/")
-        .current_dir("./target/release/")
-        .env("BONSAI_API_KEY", std::env::var("BONSAI_API_KEY").unwrap())
-        .env("BONSAI_API_URL", std::env::var("BONSAI_API_URL").unwrap())
-        .env("RPC_URL", std::env::var("RPC_URL").unwrap())
-        .env(
-            "ETH_WALLET_PRIVATE_KEY",
-            std::env::var("ETH_WALLET_PRIVATE_KEY").unwrap(),
+    validate_vote(&payload, &state.prover).await?;
+    let id = state
+        .jobs
+        .enqueue(&payload)
+        .await
+        .map_err(|e| error::ErrorInternalServerError(e.to_string()))?;
+    println!("Job queued: {}", id);
+
+    Ok(Json(JobCreated { id }))
+}
+
+/// Checks a vote as far as possible before queueing its proof: its encoding and shape, the voting
+/// power it claims and its signer. Malformed votes are rejected with 400, and votes that do not
+/// hold against the proposal's snapshot with 422.
+async fn validate_vote(payload: &VotingParams, prover: &VoteProver) -> Result<()> {
+    let request = payload
+        .vote_request()
+        .map_err(|e| error::ErrorBadRequest(format!("{:#}", e)))?;
+    check_request(&request).map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    let balance = payload
+        .balance()
+        .map_err(|e| error::ErrorBadRequest(format!("{:#}", e)))?;
+    let signature = Signature::from_str(&payload.voter_signature)
+        .map_err(|e| error::ErrorBadRequest(format!("invalid voter signature: {}", e)))?;
+
+    let quote = prover
+        .quote(&request)
+        .await
+        .map_err(|e| error::ErrorUnprocessableEntity(format!("{:#}", e)))?;
+    if let Some(balance) = balance {
+        if balance != quote.balance {
+            return Err(error::ErrorUnprocessableEntity(format!(
+                "balance {} does not match the voting power {}",
+                balance, quote.balance
+            )));
+        }
+    }
+    let signer = recover_signer(quote.message, &signature)
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    if signer != request.voter {
+        return Err(error::ErrorBadRequest(format!(
+            "the vote is signed by {}, not by the voter {}",
+            signer, request.voter
+        )));
+    }
+    Ok(())
+}
+
+#[get("/jobs/{id}")]
+async fn retrieve_job(path: web::Path<i32>, state: web::Data<AppState>) -> Result<Json<JobStatus>> {
+    let job = state
+        .jobs
+        .job(*path)
+        .await
+        .map_err(|e| error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| error::ErrorNotFound("job not found"))?;
+
+    Ok(Json(JobStatus::from(job)))
+}
+
+/// Streams the state transitions of a job as server-sent events, starting with its current
+/// state. The stream ends once the job is confirmed or failed, right away if it already is. A
+/// subscriber falling behind gets the job's state read again instead of the dropped transitions.
+#[get("/jobs/{id}/events")]
+async fn job_events(path: web::Path<i32>, state: web::Data<AppState>) -> Result<HttpResponse> {
+    let id = *path;
+    // Subscribing before reading the job makes sure no transition is missed in between.
+    let receiver = state.jobs.subscribe();
+    let job = state
+        .jobs
+        .job(id)
+        .await
+        .map_err(|e| error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| error::ErrorNotFound("job not found"))?;
+
+    let first = job.event();
+    let transitions = if JobState::is_final(&first.state) {
+        // A finished job has no transitions left, so the stream ends after its current state.
+        stream::empty().left_stream()
+    } else {
+        let jobs = state.jobs.clone();
+        stream::unfold(
+            (receiver, first.state.clone()),
+            move |(mut receiver, last_state)| {
+                let jobs = jobs.clone();
+                async move {
+                    loop {
+                        let event = match receiver.recv().await {
+                            Ok(event) if event.id == id => event,
+                            Ok(_) => continue,
+                            // Transitions were dropped, so the job is read again to catch up.
+                            Err(RecvError::Lagged(_)) => match jobs.job(id).await {
+                                Ok(Some(job)) if job.state != last_state => job.event(),
+                                Ok(Some(_)) => continue,
+                                Ok(None) | Err(_) => return None,
+                            },
+                            Err(RecvError::Closed) => return None,
+                        };
+                        let last_state = event.state.clone();
+                        return Some((event, (receiver, last_state)));
+                    }
+                }
+            },
         )
-        .arg(format!(
-            "--chain-id={}",
-            std::env::var("CHAIN_ID").unwrap_or_else(|_| "11155111".to_string())
-        ))
-        .arg(format!("--rpc-url={}", std::env::var("RPC_URL").unwrap()))
-        .arg(format!("--block-number={}", payload.block_number))
-        .arg(format!("--voter-signature={}", voter_signature))
-        .arg(format!("--voter={}", payload.voter))
-        .arg(format!("--dao-address={}", payload.dao_address))
-        .arg(format!("--proposal-id={}", payload.proposal_id))
-        .arg(format!("--direction={}", payload.direction))
-        .arg(format!("--balance={}", payload.balance))
-        .arg(format!("--config-contract={}", payload.config_contract))
-        .arg(format!("--token={}", payload.token_address))
-        .arg(format!(
-            "--additional-delegation-data={}",
-            payload.additional_delegation_data
-        ))
-        .arg(format!("--testing={}", 0))
-        .output()
-        .expect("Failed to execute command");
-    println!("Execution done");
-
-    let _success = output.status.success();
-    let message_out = String::from_utf8_lossy(&output.stdout).to_string();
-    let message_stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    println!("{}", message_out);
-    println!("{}", message_stderr);
-    Ok(Json(()))
+        .right_stream()
+    };
+    let events = stream::once(future::ready(first))
+        .chain(transitions)
+        .scan(false, |done, event| {
+            if *done {
+                return future::ready(None);
+            }
+            *done = JobState::is_final(&event.state);
+            future::ready(Some(event))
+        })
+        .map(|event| {
+            let data = serde_json::to_string(&event)?;
+            Ok::<_, error::Error>(web::Bytes::from(format!(
+                "event: state\ndata: {}\n\n",
+                data
+            )))
+        });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events))
+}
+
+#[derive(Deserialize)]
+struct ProposalQuery {
+    /// Plugin of the proposal. Read from its confirmed votes when omitted.
+    plugin: Option<String>,
+}
+
+/// The configured strategies, snapshot block, tally and voters of a proposal, aggregated from
+/// the confirmed vote jobs and checked against the plugin's events.
+#[get("/{dao}/proposals/{id}")]
+async fn retrieve_proposal(
+    path: web::Path<(String, String)>,
+    query: web::Query<ProposalQuery>,
+    state: web::Data<AppState>,
+) -> Result<Json<ProposalView>> {
+    let (dao, proposal_id) = path.into_inner();
+    let dao = Address::from_str(&dao)
+        .map_err(|e| error::ErrorBadRequest(format!("invalid DAO address: {}", e)))?;
+    let proposal_id = U256::from_str(&proposal_id)
+        .map_err(|e| error::ErrorBadRequest(format!("invalid proposal id: {}", e)))?;
+    let plugin = query
+        .plugin
+        .as_deref()
+        .map(Address::from_str)
+        .transpose()
+        .map_err(|e| error::ErrorBadRequest(format!("invalid plugin address: {}", e)))?;
+
+    let votes = state
+        .storage
+        .confirmed_votes(&dao.to_string().to_lowercase(), &proposal_id.to_string())
+        .await
+        .map_err(|e| error::ErrorInternalServerError(e.to_string()))?;
+    let plugin = match plugin {
+        Some(plugin) => plugin,
+        None => votes
+            .last()
+            .and_then(|vote| vote.config_contract.as_deref())
+            .and_then(|plugin| Address::from_str(plugin).ok())
+            .ok_or_else(|| {
+                error::ErrorNotFound("no confirmed votes on this proposal, pass its plugin")
+            })?,
+    };
+
+    let index = state
+        .indexed_plugin
+        .filter(|(indexed, _)| *indexed == plugin)
+        .map(|(_, start_block)| Index {
+            storage: state.storage.as_ref(),
+            start_block,
+        });
+    let view = proposal_view(
+        state.rpc_url.clone(),
+        plugin,
+        dao,
+        proposal_id,
+        &votes,
+        index,
+    )
+    .await
+    .map_err(|e| match e.downcast_ref::<NotIndexed>() {
+        Some(not_indexed) => error::ErrorServiceUnavailable(not_indexed.to_string()),
+        None => error::ErrorBadGateway(format!("{:#}", e)),
+    })?
+    .ok_or_else(|| error::ErrorNotFound("proposal not found"))?;
+
+    Ok(Json(view))
 }
 
 #[derive(Clone)]
 struct AppState {
-    pool: PgPool,
+    rpc_url: Url,
+    storage: Arc<dyn Storage>,
+    prover: VoteProver,
+    jobs: JobQueue,
+    /// Plugin whose events the server indexes, with the block its index starts from.
+    indexed_plugin: Option<(Address, u64)>,
 }
 
-#[shuttle_runtime::main]
-async fn main(
-    #[shuttle_shared_db::Postgres] pool: PgPool,
-) -> ShuttleActixWeb<impl FnOnce(&mut ServiceConfig) + Send + Clone + 'static> {
-    sqlx::migrate!()
-        .run(&pool)
+/// Starts the proving workers on `storage`.
+async fn app_state(
+    storage: Arc<dyn Storage>,
+    config: &Config,
+) -> anyhow::Result<web::Data<AppState>> {
+    // Votes are proven in-process with the settings of the publisher.
+    let prover = config.vote_prover()?;
+    let jobs = JobQueue::new(storage.clone(), prover.clone());
+    jobs.start(config.prover_workers)
         .await
-        .expect("Failed to run migrations");
-
-    let state = web::Data::new(AppState { pool });
-
-    let config = move |cfg: &mut ServiceConfig| {
-        cfg.service(
-            web::scope("/votes")
-                .wrap(Cors::permissive())
-                .wrap(Logger::default())
-                .service(retrieve_all_votes)
-                .service(retrieve_vote)
-                .service(add_vote)
-                .service(delete_vote)
-                .service(proof_vote)
-                .app_data(state),
-        );
+        .context("failed to start the proving workers")?;
+
+    let rpc_url = Url::from_str(&config.rpc_url).context("invalid rpc_url")?;
+    let indexed_plugin = match &config.plugin_address {
+        Some(plugin) => {
+            let plugin = Address::from_str(plugin).context("invalid plugin_address")?;
+            Indexer::new(
+                storage.clone(),
+                rpc_url.clone(),
+                plugin,
+                config.indexer_start_block,
+            )
+            .start();
+            Some((plugin, config.indexer_start_block))
+        }
+        None => None,
     };
 
-    Ok(config.into())
+    Ok(web::Data::new(AppState {
+        rpc_url,
+        storage,
+        prover,
+        jobs,
+        indexed_plugin,
+    }))
 }
 
-#[derive(Deserialize)]
-struct VoteNew {
-    account: String,
-    option: i16,
-    proposal_id: i64,
-    dao: String,
+fn configure(cfg: &mut ServiceConfig, state: web::Data<AppState>) {
+    cfg.service(
+        web::scope("/votes")
+            .wrap(Cors::permissive())
+            .wrap(Logger::default())
+            .service(retrieve_all_votes)
+            .service(retrieve_vote)
+            .service(add_vote)
+            .service(delete_vote)
+            .service(proof_vote)
+            .service(retrieve_job)
+            .service(job_events)
+            .app_data(state.clone()),
+    );
+    cfg.service(
+        web::scope("/daos")
+            .wrap(Cors::permissive())
+            .wrap(Logger::default())
+            .service(retrieve_proposal)
+            .app_data(state),
+    );
 }
 
-#[derive(Serialize, Deserialize, FromRow)]
-struct Vote {
-    id: i32,
-    account: String,
-    option: i16,
-    proposal_id: i64,
-    dao: String,
+#[cfg(feature = "shuttle")]
+#[shuttle_runtime::main]
+async fn main(
+    #[shuttle_shared_db::Postgres] pool: sqlx::PgPool,
+) -> shuttle_actix_web::ShuttleActixWeb<impl FnOnce(&mut ServiceConfig) + Send + Clone + 'static> {
+    let config = Config::load()?;
+    let state = app_state(
+        Arc::new(storage::PostgresStorage::new(pool).await?),
+        &config,
+    )
+    .await?;
+
+    Ok((move |cfg: &mut ServiceConfig| configure(cfg, state)).into())
 }
 
-#[derive(Deserialize)]
+#[cfg(not(feature = "shuttle"))]
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let database_url = config
+        .database_url
+        .as_deref()
+        .context("database_url must be set")?;
+    let state = app_state(storage::connect(database_url).await?, &config).await?;
+
+    println!("Listening on {}", config.bind_address);
+    actix_web::HttpServer::new(move || {
+        let state = state.clone();
+        actix_web::App::new().configure(move |cfg| configure(cfg, state))
+    })
+    .bind(config.bind_address)?
+    .run()
+    .await?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
 struct VotingParams {
-    block_number: String,
+    /// Snapshot block of the proposal, which the publisher reads from the plugin when omitted.
+    #[serde(default)]
+    block_number: Option<String>,
     voter_signature: String,
     voter: String,
     dao_address: String,
     proposal_id: String,
     direction: u8,
-    balance: String,
+    /// Voting power given to abstain, yes and no by a split vote (direction 0).
+    #[serde(default)]
+    amounts: Vec<String>,
+    /// Options chosen by a ballot of a multi-option proposal, most preferred first.
+    #[serde(default)]
+    choices: Vec<u8>,
+    /// Voting power the voter signed for, which the publisher computes when omitted.
+    #[serde(default)]
+    balance: Option<String>,
     config_contract: String,
     token_address: String,
     additional_delegation_data: String,
+    /// Census leaves and proofs of the assets using the `MerkleCensus` strategy.
+    #[serde(default)]
+    census_proofs: Vec<CensusProof>,
+}
+
+#[derive(Serialize)]
+struct JobCreated {
+    id: i32,
+}
+
+/// Status of a proof job. Times are Unix timestamps, and `journal` and `seal` are set once the
+/// vote is proven.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobStatus {
+    id: i32,
+    state: String,
+    error: Option<String>,
+    tx_hash: Option<String>,
+    journal: Option<serde_json::Value>,
+    seal: Option<String>,
+    vote_id: Option<i32>,
+    created_at: i64,
+    started_at: Option<i64>,
+    proved_at: Option<i64>,
+    submitted_at: Option<i64>,
+    finished_at: Option<i64>,
+}
+
+impl From<JobRow> for JobStatus {
+    fn from(job: JobRow) -> Self {
+        Self {
+            id: job.id,
+            state: job.state,
+            error: job.error,
+            tx_hash: job.tx_hash,
+            journal: job
+                .journal
+                .and_then(|journal| serde_json::from_str(&journal).ok()),
+            seal: job.seal,
+            vote_id: job.vote_id,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            proved_at: job.proved_at,
+            submitted_at: job.submitted_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
+
+impl VotingParams {
+    fn vote_request(&self) -> anyhow::Result<VoteRequest> {
+        Ok(VoteRequest {
+            block_number: self
+                .block_number
+                .as_deref()
+                .map(u64::from_str)
+                .transpose()
+                .context("invalid block number")?,
+            voter: Address::from_str(&self.voter).context("invalid voter address")?,
+            dao: Address::from_str(&self.dao_address).context("invalid DAO address")?,
+            proposal_id: U256::from_str(&self.proposal_id).context("invalid proposal id")?,
+            direction: self.direction,
+            amounts: self
+                .amounts
+                .iter()
+                .map(|amount| U256::from_str(amount))
+                .collect::<Result<_, _>>()
+                .context("invalid amounts")?,
+            choices: self.choices.clone(),
+            config_contract: Address::from_str(&self.config_contract)
+                .context("invalid config contract address")?,
+            additional_delegation_data: self.additional_delegation_data.clone(),
+            census_proofs: self.census_proofs.clone(),
+        })
+    }
+
+    fn balance(&self) -> anyhow::Result<Option<U256>> {
+        self.balance
+            .as_deref()
+            .map(U256::from_str)
+            .transpose()
+            .context("invalid balance")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use apps::ProverBuilder;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::storage::SqliteStorage;
+
+    /// The routes over an in-memory database, without workers or indexer, and with a prover that
+    /// can't reach any node.
+    async fn state() -> web::Data<AppState> {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let rpc_url = Url::parse("http://127.0.0.1:1").unwrap();
+        let prover = ProverBuilder::new(rpc_url.clone()).build_vote_prover();
+        web::Data::new(AppState {
+            rpc_url,
+            jobs: JobQueue::new(storage.clone(), prover.clone()),
+            storage,
+            prover,
+            indexed_plugin: None,
+        })
+    }
+
+    /// A config with the chain of the assets of a proposal, and no workers.
+    fn config(chain_rpc_urls: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+            rpc_url = "http://127.0.0.1:1"
+            eth_wallet_private_key = "0x0000000000000000000000000000000000000000000000000000000000000001"
+            prover_workers = 0
+            proof_kind = "succinct"
+
+            [chain_rpc_urls]
+            {}
+            "#,
+            chain_rpc_urls
+        ))
+        .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_state_is_built_from_the_config() {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let state = app_state(storage, &config(r#"10 = "http://127.0.0.1:2""#))
+            .await
+            .unwrap();
+        assert_eq!(
+            state.prover.chain_rpc_urls(),
+            &[(10, Url::parse("http://127.0.0.1:2").unwrap())]
+        );
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        let request = test::TestRequest::get().uri("/votes/jobs/1").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_chain_rpc_urls_need_chain_ids() {
+        let storage: Arc<dyn Storage> =
+            Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap());
+        let error = app_state(storage, &config(r#"optimism = "http://127.0.0.1:2""#))
+            .await
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", error).contains("invalid chain id optimism"));
+    }
+
+    #[actix_web::test]
+    async fn test_votes_are_stored() {
+        let state = state().await;
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        let request = test::TestRequest::post()
+            .uri("/votes")
+            .set_json(json!({
+                "account": "0x01",
+                "option": 2,
+                "proposal_id": U256::MAX.to_string(),
+                "dao": "0x02",
+            }))
+            .to_request();
+        let vote: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(vote["option"], 2);
+        assert_eq!(vote["proposal_id"], U256::MAX.to_string());
+
+        let request = test::TestRequest::get().uri("/votes").to_request();
+        let votes: Vec<Value> = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(votes, vec![vote]);
+    }
+
+    #[actix_web::test]
+    async fn test_jobs_are_served() {
+        let state = state().await;
+        let id = state
+            .storage
+            .insert_job("{}", "0x01", "1", "0x02")
+            .await
+            .unwrap();
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/votes/jobs/{}", id))
+            .to_request();
+        let job: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(job["id"], id);
+        assert_eq!(job["state"], JobState::Queued.as_str());
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/votes/jobs/{}", id + 1))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_events_of_a_finished_job_end_with_its_state() {
+        let state = state().await;
+        let id = state
+            .storage
+            .insert_job("{}", "0x01", "1", "0x02")
+            .await
+            .unwrap();
+        state.storage.set_job_failed(id, "boom").await.unwrap();
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        // Reading the whole body only returns once the stream ended.
+        let request = test::TestRequest::get()
+            .uri(&format!("/votes/jobs/{}/events", id))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        let data = body
+            .strip_prefix("event: state\ndata: ")
+            .and_then(|body| body.strip_suffix("\n\n"))
+            .unwrap();
+        let event: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(event["state"], JobState::Failed.as_str());
+        assert_eq!(event["error"], "boom");
+    }
+
+    #[actix_web::test]
+    async fn test_proposals_of_plugins_without_an_index_are_unavailable() {
+        let state = state().await;
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        let request = test::TestRequest::get()
+            .uri(
+                "/daos/0x0000000000000000000000000000000000000001/proposals/1\
+                 ?plugin=0x0000000000000000000000000000000000000002",
+            )
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = test::read_body(response).await;
+        assert!(std::str::from_utf8(&body)
+            .unwrap()
+            .contains("PLUGIN_ADDRESS"));
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_votes_are_rejected() {
+        let state = state().await;
+        let app = test::init_service(App::new().configure(|cfg| configure(cfg, state))).await;
+
+        let request = test::TestRequest::post()
+            .uri("/votes/vote")
+            .set_json(json!({
+                "voter_signature": "0x",
+                "voter": "not an address",
+                "dao_address": "0x0000000000000000000000000000000000000001",
+                "proposal_id": "1",
+                "direction": 2,
+                "config_contract": "0x0000000000000000000000000000000000000002",
+                "token_address": "0x0000000000000000000000000000000000000003",
+                "additional_delegation_data": "",
+            }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = test::read_body(response).await;
+        assert!(std::str::from_utf8(&body)
+            .unwrap()
+            .contains("invalid voter address"));
+    }
 }
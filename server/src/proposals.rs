@@ -0,0 +1,353 @@
+// A proposal is viewed through the votes its jobs confirmed, cross-checked against the `VoteCast`
+// and `BallotCast` events of its plugin, read from the server's index of the plugin. Scanning the
+// chain for them on every request does not scale, so plugins without an index can't be viewed.
+// As on-chain, a later vote of a voter replaces the previous one, and ballots are counted as the
+// proposal's execution strategy counts them.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use alloy::{
+    primitives::{Address, TxHash, U256},
+    providers::ProviderBuilder,
+    transports::http::reqwest::Url,
+};
+use anyhow::{ensure, Context, Result};
+use apps::IProposals;
+use serde::{Deserialize, Serialize};
+use strategies::{
+    execution_strategies::{
+        approval_winner, instant_runoff_winner, pack_choices, MAX_BALLOT_CHOICES,
+    },
+    RiscVotingProtocolConfig,
+};
+
+use crate::{
+    jobs::JournalFields,
+    storage::{ConfirmedVote, Storage},
+};
+
+/// Options of a majority proposal, as in `IMajorityVoting.VoteOption`: abstain, yes and no.
+const MAJORITY_OPTIONS: [u8; 3] = [1, 2, 3];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalView {
+    dao: Address,
+    proposal_id: String,
+    plugin: Address,
+    open: bool,
+    executed: bool,
+    snapshot_block: u64,
+    /// Number of options of a multi-option proposal, 0 for a majority proposal.
+    option_count: u8,
+    /// The proposal's config, with the strategies of its assets and its execution strategy.
+    config: Option<RiscVotingProtocolConfig>,
+    /// Voting power per option of the confirmed votes. Ballots count for every choice under
+    /// `ApprovalVoting`, and for their first choice otherwise, the first round of `RankedChoice`.
+    tally: Vec<OptionTally>,
+    /// Option the execution strategy of a multi-option proposal picks from the confirmed ballots,
+    /// 0 for none or a tie. Unset for majority proposals and unknown strategies.
+    winner: Option<u8>,
+    voters: Vec<Voter>,
+    /// Voters with a vote on-chain but no confirmed job.
+    other_voters: Vec<Address>,
+    /// Whether the events show the vote of every confirmed job.
+    consistent: bool,
+    /// Last indexed block, which can lag behind the chain. Unset until the indexer recorded a
+    /// range.
+    indexed_block: Option<u64>,
+}
+
+/// The server's index of a plugin, from the block `start_block`.
+pub struct Index<'a> {
+    pub storage: &'a dyn Storage,
+    pub start_block: u64,
+}
+
+/// The server does not index `plugin` from the snapshot block of the proposal, so its events can't
+/// be read.
+#[derive(Debug)]
+pub struct NotIndexed {
+    pub plugin: Address,
+}
+
+impl fmt::Display for NotIndexed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the server does not index plugin {} from the proposal's snapshot block, enable the \
+             indexer with PLUGIN_ADDRESS and INDEXER_START_BLOCK",
+            self.plugin
+        )
+    }
+}
+
+impl std::error::Error for NotIndexed {}
+
+/// Fields of an indexed `vote_cast` or `ballot_cast` event.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CastData {
+    #[serde(default)]
+    vote_option: u8,
+    #[serde(default)]
+    choices: Vec<u8>,
+    voting_power: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OptionTally {
+    option: u8,
+    voting_power: String,
+}
+
+/// The latest confirmed vote of a voter.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Voter {
+    voter: Address,
+    job_id: i32,
+    tx_hash: Option<String>,
+    voting_power: String,
+    direction: u8,
+    amounts: Vec<String>,
+    choices: Vec<u8>,
+    /// Whether the events of the plugin show the same vote.
+    on_chain: bool,
+}
+
+/// A vote as the plugin records it.
+#[derive(Debug, PartialEq, Eq)]
+enum Cast {
+    /// Voting power per option, without the options it gives none.
+    Vote(BTreeMap<u8, U256>),
+    Ballot(Vec<u8>, U256),
+}
+
+impl Cast {
+    fn from_journal(journal: &JournalFields) -> Result<Self> {
+        let balance = U256::from_str(&journal.balance).context("invalid balance")?;
+        if !journal.choices.is_empty() {
+            return Ok(Cast::Ballot(journal.choices.clone(), balance));
+        }
+        let mut power = BTreeMap::new();
+        if journal.direction == 0 {
+            for (option, amount) in MAJORITY_OPTIONS.iter().zip(&journal.amounts) {
+                let amount = U256::from_str(amount).context("invalid amount")?;
+                if !amount.is_zero() {
+                    power.insert(*option, amount);
+                }
+            }
+        } else {
+            power.insert(journal.direction, balance);
+        }
+        Ok(Cast::Vote(power))
+    }
+}
+
+/// Builds the view of the proposal `proposal_id` of the plugin `plugin` from its confirmed
+/// `votes` and the plugin's `index`. Returns `None` if the proposal does not exist, or the plugin
+/// does not belong to `dao`, and fails with [`NotIndexed`] without an index covering the proposal.
+pub async fn proposal_view(
+    rpc_url: Url,
+    plugin: Address,
+    dao: Address,
+    proposal_id: U256,
+    votes: &[ConfirmedVote],
+    index: Option<Index<'_>>,
+) -> Result<Option<ProposalView>> {
+    let index = index.ok_or(NotIndexed { plugin })?;
+    let provider = ProviderBuilder::new().on_http(rpc_url);
+    let contract = IProposals::new(plugin, &provider);
+    if contract.dao().call().await?._0 != dao {
+        return Ok(None);
+    }
+    let proposal = contract.getProposal(proposal_id).call().await?;
+    let parameters = &proposal.parameters;
+    if parameters.snapshotBlock == 0 {
+        return Ok(None);
+    }
+    let config: Option<RiscVotingProtocolConfig> =
+        serde_json::from_str(&parameters.votingProtocolConfig).ok();
+    let execution_strategy = config
+        .as_ref()
+        .and_then(|config| config.execution_strategy_for(parameters.optionCount));
+    // An index that starts after the snapshot block may miss votes.
+    if index.start_block > parameters.snapshotBlock {
+        return Err(NotIndexed { plugin }.into());
+    }
+    let indexed_plugin = plugin.to_string().to_lowercase();
+    let chain_casts = indexed_casts(index.storage, &indexed_plugin, proposal_id).await?;
+    let indexed_block = index.storage.indexed_blocks(&indexed_plugin, 1).await?;
+    let indexed_block = match indexed_block.first() {
+        Some((number, _)) => Some(u64::try_from(*number)?),
+        None => None,
+    };
+
+    let mut latest = BTreeMap::new();
+    for vote in votes {
+        let journal: JournalFields =
+            serde_json::from_str(&vote.journal).context("invalid journal")?;
+        let voter = Address::from_str(&journal.voter).context("invalid voter")?;
+        latest.insert(voter, (vote, journal));
+    }
+
+    let options: Vec<u8> = match parameters.optionCount {
+        0 => MAJORITY_OPTIONS.to_vec(),
+        option_count => (1..=option_count).collect(),
+    };
+    let mut tally: BTreeMap<u8, U256> =
+        options.iter().map(|option| (*option, U256::ZERO)).collect();
+    let mut ballots = Vec::new();
+    let mut voters = Vec::new();
+    for (voter, (vote, journal)) in latest.iter() {
+        let cast = Cast::from_journal(journal)?;
+        match &cast {
+            Cast::Vote(power) => {
+                for (option, power) in power {
+                    *tally.entry(*option).or_default() += *power;
+                }
+            }
+            Cast::Ballot(choices, power) => {
+                ensure!(choices.len() <= MAX_BALLOT_CHOICES, "too many choices");
+                for option in counted_choices(execution_strategy, choices) {
+                    *tally.entry(*option).or_default() += *power;
+                }
+                ballots.extend([*power, pack_choices(choices)]);
+            }
+        }
+        voters.push(Voter {
+            voter: *voter,
+            job_id: vote.job_id,
+            tx_hash: vote.tx_hash.clone(),
+            voting_power: journal.balance.clone(),
+            direction: journal.direction,
+            amounts: journal.amounts.clone(),
+            choices: journal.choices.clone(),
+            on_chain: chain_casts.get(voter) == Some(&cast),
+        });
+    }
+
+    Ok(Some(ProposalView {
+        dao,
+        proposal_id: proposal_id.to_string(),
+        plugin,
+        open: proposal.open,
+        executed: proposal.executed,
+        snapshot_block: parameters.snapshotBlock,
+        option_count: parameters.optionCount,
+        tally: tally
+            .into_iter()
+            .map(|(option, power)| OptionTally {
+                option,
+                voting_power: power.to_string(),
+            })
+            .collect(),
+        winner: match (parameters.optionCount, execution_strategy) {
+            (0, _) => None,
+            (_, Some("ApprovalVoting")) => Some(approval_winner(&ballots)),
+            (_, Some("RankedChoice")) => Some(instant_runoff_winner(&ballots)),
+            _ => None,
+        },
+        config,
+        consistent: voters.iter().all(|voter| voter.on_chain),
+        indexed_block,
+        voters,
+        other_voters: chain_casts
+            .keys()
+            .filter(|voter| !latest.contains_key(*voter))
+            .copied()
+            .collect(),
+    }))
+}
+
+/// Choices a ballot gives its voting power to in the tally: all of them under `ApprovalVoting`,
+/// and the first one otherwise.
+fn counted_choices<'a>(execution_strategy: Option<&str>, choices: &'a [u8]) -> &'a [u8] {
+    match execution_strategy {
+        Some("ApprovalVoting") => choices,
+        _ => &choices[..choices.len().min(1)],
+    }
+}
+
+/// Replays the indexed vote events of a proposal into the current vote of each voter.
+async fn indexed_casts(
+    storage: &dyn Storage,
+    plugin: &str,
+    proposal_id: U256,
+) -> Result<BTreeMap<Address, Cast>> {
+    let mut events = Vec::new();
+    for cast in storage
+        .indexed_casts(plugin, &proposal_id.to_string())
+        .await?
+    {
+        let voter = cast
+            .account
+            .as_deref()
+            .context("indexed vote without a voter")?;
+        let voter = Address::from_str(voter).context("invalid indexed voter")?;
+        let tx_hash = TxHash::from_str(&cast.tx_hash).context("invalid indexed transaction")?;
+        let data: CastData = serde_json::from_str(&cast.data).context("invalid indexed vote")?;
+        let power = U256::from_str(&data.voting_power).context("invalid voting power")?;
+        let cast = if cast.kind == "ballot_cast" {
+            Cast::Ballot(data.choices, power)
+        } else {
+            Cast::Vote(BTreeMap::from([(data.vote_option, power)]))
+        };
+        events.push((voter, Some(tx_hash), cast));
+    }
+    Ok(replay(events))
+}
+
+/// Folds vote events, in chain order, into the current vote of each voter.
+fn replay(events: Vec<(Address, Option<TxHash>, Cast)>) -> BTreeMap<Address, Cast> {
+    let mut casts = BTreeMap::new();
+    let mut cast_txs: BTreeMap<Address, Option<TxHash>> = BTreeMap::new();
+    for (voter, tx_hash, cast) in events {
+        // The events of a split vote share its transaction, any other replaces the vote.
+        let same_tx = cast_txs.insert(voter, tx_hash) == Some(tx_hash);
+        if same_tx {
+            if let (Some(Cast::Vote(power)), Cast::Vote(more)) = (casts.get_mut(&voter), &cast) {
+                power.extend(more.clone());
+                continue;
+            }
+        }
+        casts.insert(voter, cast);
+    }
+    casts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_votes_merge_and_later_votes_replace() {
+        let voter = Address::repeat_byte(1);
+        let split = Some(TxHash::repeat_byte(1));
+        let later = Some(TxHash::repeat_byte(2));
+        let vote =
+            |option: u8, power: u64| Cast::Vote(BTreeMap::from([(option, U256::from(power))]));
+
+        let casts = replay(vec![(voter, split, vote(2, 3)), (voter, split, vote(3, 4))]);
+        let expected = BTreeMap::from([(2, U256::from(3)), (3, U256::from(4))]);
+        assert_eq!(casts[&voter], Cast::Vote(expected));
+
+        let casts = replay(vec![
+            (voter, split, vote(2, 3)),
+            (voter, later, vote(3, 7)),
+            (voter, later, Cast::Ballot(vec![1], U256::from(7))),
+        ]);
+        assert_eq!(casts[&voter], Cast::Ballot(vec![1], U256::from(7)));
+    }
+
+    #[test]
+    fn test_ballots_count_as_their_strategy_counts_them() {
+        let choices = [2, 1, 3];
+        assert_eq!(counted_choices(Some("ApprovalVoting"), &choices), [2, 1, 3]);
+        assert_eq!(counted_choices(Some("RankedChoice"), &choices), [2]);
+        assert_eq!(counted_choices(None, &choices), [2]);
+        assert!(counted_choices(Some("RankedChoice"), &[]).is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+// Persistence of the votes, of the proof jobs and of the indexed plugin events, on Postgres or
+// SQLite. Each backend runs its own migrations, from `migrations/postgres` and `migrations/sqlite`.
+
+use std::{str::FromStr, sync::Arc};
+
+use alloy::primitives::U256;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize, Serializer};
+use sqlx::FromRow;
+
+use crate::jobs::{JobRow, JobState};
+
+mod postgres;
+mod sqlite;
+pub use postgres::PostgresStorage;
+pub use sqlite::SqliteStorage;
+
+#[derive(Deserialize)]
+pub struct VoteNew {
+    pub account: String,
+    pub option: i16,
+    pub proposal_id: U256,
+    pub dao: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Vote {
+    pub id: i32,
+    pub account: String,
+    pub option: i16,
+    #[serde(serialize_with = "serialize_decimal")]
+    pub proposal_id: U256,
+    pub dao: String,
+}
+
+/// A vote as stored, with its proposal id in decimal.
+#[derive(FromRow)]
+struct VoteRow {
+    id: i32,
+    account: String,
+    option: i16,
+    proposal_id: String,
+    dao: String,
+}
+
+impl TryFrom<VoteRow> for Vote {
+    type Error = sqlx::Error;
+
+    fn try_from(row: VoteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.id,
+            account: row.account,
+            option: row.option,
+            proposal_id: U256::from_str(&row.proposal_id)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            dao: row.dao,
+        })
+    }
+}
+
+/// Proposal ids are served in decimal, like everywhere else in the API.
+fn serialize_decimal<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// A vote confirmed by a job. Addresses are lowercase and proposal ids decimal.
+#[derive(FromRow)]
+pub struct ConfirmedVote {
+    pub job_id: i32,
+    pub config_contract: Option<String>,
+    /// Journal fields of the proven vote, as JSON.
+    pub journal: String,
+    pub tx_hash: Option<String>,
+}
+
+/// An event of a plugin, with the journal of the proof its transaction carried. Addresses and
+/// hashes are lowercase hex, and proposal ids decimal.
+pub struct IndexedEvent {
+    pub block_number: i64,
+    pub block_hash: String,
+    pub tx_hash: String,
+    pub log_index: i64,
+    /// `proposal_created`, `vote_cast`, `ballot_cast`, `winning_option_chosen` or
+    /// `proposal_executed`.
+    pub kind: &'static str,
+    pub proposal_id: String,
+    /// Creator of a proposal, or voter.
+    pub account: Option<String>,
+    /// Fields of the event, as JSON.
+    pub data: String,
+    /// Journal fields of the proof, as JSON, when the transaction called the plugin directly.
+    pub journal: Option<String>,
+}
+
+/// A `vote_cast` or `ballot_cast` event read back from the index.
+#[derive(FromRow)]
+pub struct IndexedCast {
+    pub tx_hash: String,
+    pub kind: String,
+    pub account: Option<String>,
+    /// Fields of the event, as JSON.
+    pub data: String,
+}
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn votes(&self) -> sqlx::Result<Vec<Vote>>;
+
+    async fn vote(&self, id: i32) -> sqlx::Result<Vote>;
+
+    async fn add_vote(&self, vote: &VoteNew) -> sqlx::Result<Vote>;
+
+    async fn delete_vote(&self, id: i32) -> sqlx::Result<()>;
+
+    async fn job(&self, id: i32) -> sqlx::Result<Option<JobRow>>;
+
+    /// Stores a queued job for a vote on the proposal `proposal_id` of `dao`, made through the
+    /// plugin `config_contract`, and returns its id.
+    async fn insert_job(
+        &self,
+        request: &str,
+        dao: &str,
+        proposal_id: &str,
+        config_contract: &str,
+    ) -> sqlx::Result<i32>;
+
+    /// Queues the jobs that had not been sent again, and fails the sent ones with `error`.
+    async fn recover_jobs(&self, error: &str) -> sqlx::Result<()>;
+
+    /// Marks the oldest queued job no other worker started as started, and returns its id and
+    /// request.
+    async fn claim_job(&self) -> sqlx::Result<Option<(i32, String)>>;
+
+    async fn set_job_state(&self, id: i32, state: JobState) -> sqlx::Result<()>;
+
+    async fn set_job_proof(&self, id: i32, journal: &str, seal: &str) -> sqlx::Result<()>;
+
+    async fn set_job_submitted(&self, id: i32, tx_hash: &str) -> sqlx::Result<()>;
+
+    /// Confirms a job, recording `vote` in the votes when given.
+    async fn set_job_confirmed(&self, id: i32, vote: Option<&VoteNew>) -> sqlx::Result<()>;
+
+    async fn set_job_failed(&self, id: i32, error: &str) -> sqlx::Result<()>;
+
+    /// Confirmed votes on the proposal `proposal_id` of `dao`, in the order they were confirmed.
+    async fn confirmed_votes(
+        &self,
+        dao: &str,
+        proposal_id: &str,
+    ) -> sqlx::Result<Vec<ConfirmedVote>>;
+
+    /// Up to `limit` blocks indexed for `plugin`, with their hashes, the most recent first.
+    async fn indexed_blocks(&self, plugin: &str, limit: i64) -> sqlx::Result<Vec<(i64, String)>>;
+
+    /// Stores the `events` of `plugin` and the hashes of their `blocks` at once.
+    async fn insert_indexed(
+        &self,
+        plugin: &str,
+        blocks: &[(i64, String)],
+        events: &[IndexedEvent],
+    ) -> sqlx::Result<()>;
+
+    /// Forgets the blocks and events of `plugin` after the block `number`.
+    async fn rewind_index(&self, plugin: &str, number: i64) -> sqlx::Result<()>;
+
+    /// Indexed vote and ballot events of the proposal `proposal_id` of `plugin`, in chain order.
+    async fn indexed_casts(
+        &self,
+        plugin: &str,
+        proposal_id: &str,
+    ) -> sqlx::Result<Vec<IndexedCast>>;
+}
+
+/// Connects to the database at `database_url`, SQLite for `sqlite:` URLs and Postgres otherwise,
+/// and runs its migrations.
+#[cfg_attr(feature = "shuttle", allow(dead_code))]
+pub async fn connect(database_url: &str) -> Result<Arc<dyn Storage>> {
+    if database_url.starts_with("sqlite:") {
+        let storage = SqliteStorage::connect(database_url)
+            .await
+            .context("failed to open the SQLite database")?;
+        Ok(Arc::new(storage))
+    } else {
+        let pool = sqlx::PgPool::connect(database_url)
+            .await
+            .context("failed to connect to the database")?;
+        Ok(Arc::new(PostgresStorage::new(pool).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+    use super::*;
+
+    const DAO: &str = "0x0000000000000000000000000000000000000001";
+    const PLUGIN: &str = "0x0000000000000000000000000000000000000002";
+
+    async fn sqlite_storage() -> Arc<dyn Storage> {
+        Arc::new(SqliteStorage::connect("sqlite::memory:").await.unwrap())
+    }
+
+    /// A storage in a new schema of the Postgres database at `DATABASE_URL`.
+    async fn postgres_storage() -> Arc<dyn Storage> {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must point to Postgres");
+        assert!(
+            !url.starts_with("sqlite:"),
+            "DATABASE_URL must point to Postgres"
+        );
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let schema = format!("storage_test_{}_{}", std::process::id(), nanos);
+        let pool = sqlx::PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+        Arc::new(PostgresStorage::new(pool).await.unwrap())
+    }
+
+    async fn insert_job(storage: &Arc<dyn Storage>, request: &str) -> i32 {
+        storage.insert_job(request, DAO, "1", PLUGIN).await.unwrap()
+    }
+
+    async fn job_row(storage: &Arc<dyn Storage>, id: i32) -> JobRow {
+        storage.job(id).await.unwrap().unwrap()
+    }
+
+    fn event(block_number: i64, block_hash: &str) -> IndexedEvent {
+        IndexedEvent {
+            block_number,
+            block_hash: block_hash.to_string(),
+            tx_hash: format!("0x{:064x}", block_number),
+            log_index: 0,
+            kind: "vote_cast",
+            proposal_id: "1".to_string(),
+            account: Some(DAO.to_string()),
+            data: "{}".to_string(),
+            journal: None,
+        }
+    }
+
+    async fn check_claim_job(storage: Arc<dyn Storage>) {
+        let first = insert_job(&storage, "first").await;
+        let second = insert_job(&storage, "second").await;
+        let failed = insert_job(&storage, "failed").await;
+        storage.set_job_failed(failed, "error").await.unwrap();
+
+        assert_eq!(
+            storage.claim_job().await.unwrap(),
+            Some((first, "first".to_string()))
+        );
+        assert_eq!(
+            storage.claim_job().await.unwrap(),
+            Some((second, "second".to_string()))
+        );
+        assert_eq!(storage.claim_job().await.unwrap(), None);
+
+        let job = job_row(&storage, first).await;
+        assert_eq!(job.state, JobState::Queued.as_str());
+        assert!(job.started_at.is_some());
+    }
+
+    async fn check_recover_jobs(storage: Arc<dyn Storage>) {
+        let claimed = insert_job(&storage, "claimed").await;
+        let preflighted = insert_job(&storage, "preflighted").await;
+        let proving = insert_job(&storage, "proving").await;
+        let submitted = insert_job(&storage, "submitted").await;
+        let confirmed = insert_job(&storage, "confirmed").await;
+        for _ in 0..5 {
+            storage.claim_job().await.unwrap().unwrap();
+        }
+        storage
+            .set_job_state(preflighted, JobState::Preflighted)
+            .await
+            .unwrap();
+        storage
+            .set_job_state(proving, JobState::Proving)
+            .await
+            .unwrap();
+        storage.set_job_submitted(submitted, "0x01").await.unwrap();
+        storage.set_job_confirmed(confirmed, None).await.unwrap();
+
+        storage.recover_jobs("restarted").await.unwrap();
+
+        for id in [claimed, preflighted, proving] {
+            let job = job_row(&storage, id).await;
+            assert_eq!(job.state, JobState::Queued.as_str());
+            assert_eq!(job.started_at, None);
+        }
+        let job = job_row(&storage, submitted).await;
+        assert_eq!(job.state, JobState::Failed.as_str());
+        assert_eq!(job.error.as_deref(), Some("restarted"));
+        assert!(job.finished_at.is_some());
+        let job = job_row(&storage, confirmed).await;
+        assert_eq!(job.state, JobState::Confirmed.as_str());
+        assert_eq!(job.error, None);
+
+        assert_eq!(storage.claim_job().await.unwrap().unwrap().0, claimed);
+        assert_eq!(storage.claim_job().await.unwrap().unwrap().0, preflighted);
+        assert_eq!(storage.claim_job().await.unwrap().unwrap().0, proving);
+        assert_eq!(storage.claim_job().await.unwrap(), None);
+    }
+
+    async fn check_set_job_confirmed(storage: Arc<dyn Storage>) {
+        let with_vote = insert_job(&storage, "with vote").await;
+        let without_vote = insert_job(&storage, "without vote").await;
+        let vote = VoteNew {
+            account: DAO.to_string(),
+            option: 2,
+            proposal_id: U256::MAX,
+            dao: DAO.to_string(),
+        };
+
+        storage
+            .set_job_confirmed(with_vote, Some(&vote))
+            .await
+            .unwrap();
+        storage.set_job_confirmed(without_vote, None).await.unwrap();
+
+        let job = job_row(&storage, with_vote).await;
+        assert_eq!(job.state, JobState::Confirmed.as_str());
+        assert!(job.finished_at.is_some());
+        let stored = storage.vote(job.vote_id.unwrap()).await.unwrap();
+        assert_eq!(stored.account, vote.account);
+        assert_eq!(stored.option, vote.option);
+        assert_eq!(stored.proposal_id, vote.proposal_id);
+        assert_eq!(stored.dao, vote.dao);
+        let job = job_row(&storage, without_vote).await;
+        assert_eq!(job.state, JobState::Confirmed.as_str());
+        assert_eq!(job.vote_id, None);
+        assert_eq!(storage.votes().await.unwrap().len(), 1);
+    }
+
+    async fn check_rewind_index(storage: Arc<dyn Storage>) {
+        let blocks: Vec<(i64, String)> = (1..=3).map(|n| (n, format!("0x{:064x}", n))).collect();
+        let events: Vec<IndexedEvent> = blocks.iter().map(|(n, hash)| event(*n, hash)).collect();
+        storage
+            .insert_indexed(PLUGIN, &blocks, &events)
+            .await
+            .unwrap();
+        // Logs of another plugin in the same block have their own index.
+        let other = IndexedEvent {
+            log_index: 1,
+            ..event(1, &blocks[0].1)
+        };
+        storage
+            .insert_indexed(DAO, &blocks, &[other])
+            .await
+            .unwrap();
+
+        storage.rewind_index(PLUGIN, 1).await.unwrap();
+
+        assert_eq!(
+            storage.indexed_blocks(PLUGIN, 10).await.unwrap(),
+            blocks[..1].to_vec()
+        );
+        let casts = storage.indexed_casts(PLUGIN, "1").await.unwrap();
+        assert_eq!(casts.len(), 1);
+        assert_eq!(casts[0].tx_hash, events[0].tx_hash);
+        // Other plugins keep their blocks and events.
+        assert_eq!(storage.indexed_blocks(DAO, 10).await.unwrap().len(), 3);
+        assert_eq!(storage.indexed_casts(DAO, "1").await.unwrap().len(), 1);
+
+        // The blocks of the new branch replace the rewound ones.
+        let fork = (2, format!("0x{:064x}", 20));
+        storage
+            .insert_indexed(PLUGIN, &[fork.clone()], &[event(fork.0, &fork.1)])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.indexed_blocks(PLUGIN, 10).await.unwrap(),
+            vec![fork, blocks[0].clone()]
+        );
+        let casts = storage.indexed_casts(PLUGIN, "1").await.unwrap();
+        assert_eq!(casts.len(), 2);
+        assert_eq!(casts[1].kind, "vote_cast");
+        assert!(storage.indexed_casts(PLUGIN, "2").await.unwrap().is_empty());
+    }
+
+    macro_rules! storage_tests {
+        ($($check:ident),*) => {
+            mod sqlite {
+                $(
+                    #[actix_web::test]
+                    async fn $check() {
+                        super::$check(super::sqlite_storage().await).await;
+                    }
+                )*
+            }
+
+            /// Run with `--ignored` and `DATABASE_URL` pointing to a Postgres database.
+            mod postgres {
+                $(
+                    #[actix_web::test]
+                    #[ignore = "needs DATABASE_URL"]
+                    async fn $check() {
+                        super::$check(super::postgres_storage().await).await;
+                    }
+                )*
+            }
+        };
+    }
+
+    storage_tests!(
+        check_claim_job,
+        check_recover_jobs,
+        check_set_job_confirmed,
+        check_rewind_index
+    );
+}
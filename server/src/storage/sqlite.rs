@@ -0,0 +1,313 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+
+use super::{ConfirmedVote, IndexedCast, IndexedEvent, Storage, Vote, VoteNew, VoteRow};
+use crate::jobs::{JobRow, JobState};
+
+/// Stores the times as Unix timestamps.
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Opens the database at `database_url`, creating it when missing, and runs the migrations.
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        // An in-memory database lives as long as its connection, so it is kept in a single one.
+        let pool_options = if database_url.contains(":memory:") {
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            SqlitePoolOptions::new()
+        };
+        let pool = pool_options.connect_with(options).await?;
+        sqlx::migrate!("migrations/sqlite")
+            .run(&pool)
+            .await
+            .context("failed to run migrations")?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn votes(&self) -> sqlx::Result<Vec<Vote>> {
+        sqlx::query_as::<_, VoteRow>("SELECT * FROM votes ORDER BY id DESC")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(Vote::try_from)
+            .collect()
+    }
+
+    async fn vote(&self, id: i32) -> sqlx::Result<Vote> {
+        sqlx::query_as::<_, VoteRow>("SELECT * FROM votes WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?
+            .try_into()
+    }
+
+    async fn add_vote(&self, vote: &VoteNew) -> sqlx::Result<Vote> {
+        sqlx::query_as::<_, VoteRow>("INSERT INTO votes(account, option, proposal_id, dao) VALUES (?1, ?2, ?3, ?4) RETURNING id, account, option, proposal_id, dao")
+            .bind(&vote.account)
+            .bind(vote.option)
+            .bind(vote.proposal_id.to_string())
+            .bind(&vote.dao)
+            .fetch_one(&self.pool)
+            .await?
+            .try_into()
+    }
+
+    async fn delete_vote(&self, id: i32) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM votes WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn job(&self, id: i32) -> sqlx::Result<Option<JobRow>> {
+        sqlx::query_as(
+            "SELECT id, state, error, tx_hash, journal, seal, vote_id, created_at, started_at, \
+             proved_at, submitted_at, finished_at FROM vote_jobs WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert_job(
+        &self,
+        request: &str,
+        dao: &str,
+        proposal_id: &str,
+        config_contract: &str,
+    ) -> sqlx::Result<i32> {
+        sqlx::query_scalar(
+            "INSERT INTO vote_jobs(state, request, dao, proposal_id, config_contract) VALUES (?1, ?2, ?3, ?4, ?5) RETURNING id",
+        )
+        .bind(JobState::Queued.as_str())
+        .bind(request)
+        .bind(dao)
+        .bind(proposal_id)
+        .bind(config_contract)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn recover_jobs(&self, error: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "UPDATE vote_jobs SET state = ?1, started_at = NULL, updated_at = unixepoch() WHERE state IN (?1, ?2, ?3)",
+        )
+        .bind(JobState::Queued.as_str())
+        .bind(JobState::Preflighted.as_str())
+        .bind(JobState::Proving.as_str())
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            "UPDATE vote_jobs SET state = ?1, error = ?2, finished_at = unixepoch(), updated_at = unixepoch() WHERE state = ?3",
+        )
+        .bind(JobState::Failed.as_str())
+        .bind(error)
+        .bind(JobState::Submitted.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn claim_job(&self) -> sqlx::Result<Option<(i32, String)>> {
+        // SQLite runs one write at a time, so no other worker can claim the job in between.
+        sqlx::query_as(
+            "UPDATE vote_jobs SET started_at = unixepoch(), updated_at = unixepoch() \
+             WHERE id = (SELECT id FROM vote_jobs WHERE state = ?1 AND started_at IS NULL \
+             ORDER BY id LIMIT 1) \
+             RETURNING id, request",
+        )
+        .bind(JobState::Queued.as_str())
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn set_job_state(&self, id: i32, state: JobState) -> sqlx::Result<()> {
+        sqlx::query("UPDATE vote_jobs SET state = ?1, updated_at = unixepoch() WHERE id = ?2")
+            .bind(state.as_str())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_job_proof(&self, id: i32, journal: &str, seal: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "UPDATE vote_jobs SET journal = ?1, seal = ?2, proved_at = unixepoch(), updated_at = unixepoch() WHERE id = ?3",
+        )
+        .bind(journal)
+        .bind(seal)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_job_submitted(&self, id: i32, tx_hash: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "UPDATE vote_jobs SET state = ?1, tx_hash = ?2, submitted_at = unixepoch(), updated_at = unixepoch() WHERE id = ?3",
+        )
+        .bind(JobState::Submitted.as_str())
+        .bind(tx_hash)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_job_confirmed(&self, id: i32, vote: Option<&VoteNew>) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let vote_id: Option<i32> = match vote {
+            Some(vote) => Some(
+                sqlx::query_scalar(
+                    "INSERT INTO votes(account, option, proposal_id, dao) VALUES (?1, ?2, ?3, ?4) RETURNING id",
+                )
+                .bind(&vote.account)
+                .bind(vote.option)
+                .bind(vote.proposal_id.to_string())
+                .bind(&vote.dao)
+                .fetch_one(&mut *tx)
+                .await?,
+            ),
+            None => None,
+        };
+        sqlx::query(
+            "UPDATE vote_jobs SET state = ?1, vote_id = ?2, finished_at = unixepoch(), updated_at = unixepoch() WHERE id = ?3",
+        )
+        .bind(JobState::Confirmed.as_str())
+        .bind(vote_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    async fn set_job_failed(&self, id: i32, error: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "UPDATE vote_jobs SET state = ?1, error = ?2, finished_at = unixepoch(), updated_at = unixepoch() WHERE id = ?3",
+        )
+        .bind(JobState::Failed.as_str())
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn confirmed_votes(
+        &self,
+        dao: &str,
+        proposal_id: &str,
+    ) -> sqlx::Result<Vec<ConfirmedVote>> {
+        sqlx::query_as(
+            "SELECT id AS job_id, config_contract, journal, tx_hash FROM vote_jobs \
+             WHERE dao = ?1 AND proposal_id = ?2 AND state = ?3 AND journal IS NOT NULL \
+             ORDER BY finished_at, id",
+        )
+        .bind(dao)
+        .bind(proposal_id)
+        .bind(JobState::Confirmed.as_str())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn indexed_blocks(&self, plugin: &str, limit: i64) -> sqlx::Result<Vec<(i64, String)>> {
+        sqlx::query_as(
+            "SELECT number, hash FROM indexed_blocks WHERE plugin = ?1 ORDER BY number DESC LIMIT ?2",
+        )
+        .bind(plugin)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn insert_indexed(
+        &self,
+        plugin: &str,
+        blocks: &[(i64, String)],
+        events: &[IndexedEvent],
+    ) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (number, hash) in blocks {
+            sqlx::query(
+                "INSERT INTO indexed_blocks(plugin, number, hash) VALUES (?1, ?2, ?3) \
+                 ON CONFLICT (plugin, number) DO UPDATE SET hash = excluded.hash",
+            )
+            .bind(plugin)
+            .bind(number)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for event in events {
+            sqlx::query(
+                "INSERT INTO indexed_events(plugin, block_number, block_hash, tx_hash, log_index, \
+                 kind, proposal_id, account, data, journal) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) \
+                 ON CONFLICT (block_hash, log_index) DO NOTHING",
+            )
+            .bind(plugin)
+            .bind(event.block_number)
+            .bind(&event.block_hash)
+            .bind(&event.tx_hash)
+            .bind(event.log_index)
+            .bind(event.kind)
+            .bind(&event.proposal_id)
+            .bind(&event.account)
+            .bind(&event.data)
+            .bind(&event.journal)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    async fn rewind_index(&self, plugin: &str, number: i64) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM indexed_events WHERE plugin = ?1 AND block_number > ?2")
+            .bind(plugin)
+            .bind(number)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM indexed_blocks WHERE plugin = ?1 AND number > ?2")
+            .bind(plugin)
+            .bind(number)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    async fn indexed_casts(
+        &self,
+        plugin: &str,
+        proposal_id: &str,
+    ) -> sqlx::Result<Vec<IndexedCast>> {
+        sqlx::query_as(
+            "SELECT tx_hash, kind, account, data FROM indexed_events \
+             WHERE plugin = ?1 AND proposal_id = ?2 AND kind IN (?3, ?4) \
+             ORDER BY block_number, log_index",
+        )
+        .bind(plugin)
+        .bind(proposal_id)
+        .bind("vote_cast")
+        .bind("ballot_cast")
+        .fetch_all(&self.pool)
+        .await
+    }
+}
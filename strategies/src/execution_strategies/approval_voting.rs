@@ -0,0 +1,88 @@
+use crate::{GuestEvmEnv, Ratio};
+
+use super::{ballots_pass, decode_ballots, ProtocolExecutionStrategy};
+use alloy_primitives::U256;
+use std::collections::BTreeMap;
+
+/// Every option of a ballot gets its full voting power, and the most approved option wins once
+/// the ballots reach the config's `minParticipation` of the total supply.
+#[derive(Default)]
+pub struct ApprovalVoting {
+    min_participation: Option<Ratio>,
+}
+
+impl ApprovalVoting {
+    pub fn new(min_participation: Option<Ratio>) -> Self {
+        Self { min_participation }
+    }
+}
+
+impl ProtocolExecutionStrategy for ApprovalVoting {
+    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool {
+        ballots_pass(
+            total_supply,
+            tally,
+            self.min_participation.as_ref(),
+            approval_winner,
+        )
+    }
+
+    fn winner(&self, tally: &[U256]) -> u8 {
+        approval_winner(tally)
+    }
+}
+
+/// Option approved by the most voting power, or 0 if there are no votes or the first place is
+/// tied.
+pub fn approval_winner(tally: &[U256]) -> u8 {
+    let Some(ballots) = decode_ballots(tally) else {
+        return 0;
+    };
+
+    let mut approvals: BTreeMap<u8, U256> = BTreeMap::new();
+    for (voting_power, choices) in &ballots {
+        for choice in choices {
+            *approvals.entry(*choice).or_default() += *voting_power;
+        }
+    }
+
+    let Some(most_approvals) = approvals.values().max().copied() else {
+        return 0;
+    };
+    let mut leaders = approvals
+        .iter()
+        .filter(|(_, approvals)| **approvals == most_approvals);
+    match (leaders.next(), leaders.next()) {
+        (Some((option, _)), None) if most_approvals > U256::ZERO => *option,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_strategies::pack_choices;
+
+    #[test]
+    fn test_most_approved_option_wins() {
+        let tally = [
+            U256::from(5),
+            pack_choices(&[1, 2]),
+            U256::from(3),
+            pack_choices(&[2]),
+        ];
+        assert_eq!(approval_winner(&tally), 2);
+    }
+
+    #[test]
+    fn test_tie_has_no_winner() {
+        let tally = [
+            U256::from(4),
+            pack_choices(&[1]),
+            U256::from(4),
+            pack_choices(&[2]),
+        ];
+        assert_eq!(approval_winner(&tally), 0);
+        assert_eq!(approval_winner(&[]), 0);
+    }
+}
@@ -5,7 +5,12 @@ use alloy_primitives::U256;
 
 pub struct MajorityVoting;
 impl ProtocolExecutionStrategy for MajorityVoting {
-    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: [U256; 3]) -> bool {
+    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool {
+        // Majority proposals are tallied as yes, no and abstain.
+        if tally.len() != 3 {
+            return false;
+        }
+
         // TODO: The parameters for the minimum partticipation and so on should be flexible
         let yes_votes = tally[0];
         let no_votes = tally[1];
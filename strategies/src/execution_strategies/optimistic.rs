@@ -0,0 +1,77 @@
+use crate::{GuestEvmEnv, Ratio};
+
+use super::ProtocolExecutionStrategy;
+use alloy_primitives::U256;
+
+/// Passes unless the no votes reach the config's `vetoThreshold` of the total supply. Yes votes
+/// are irrelevant.
+#[derive(Default)]
+pub struct Optimistic {
+    veto_threshold: Option<Ratio>,
+}
+
+impl Optimistic {
+    pub fn new(veto_threshold: Option<Ratio>) -> Self {
+        Self { veto_threshold }
+    }
+}
+
+impl ProtocolExecutionStrategy for Optimistic {
+    fn proof_execution(&self, _env: &GuestEvmEnv, total_supply: U256, tally: &[U256]) -> bool {
+        // Optimistic proposals are tallied as yes, no and abstain.
+        if tally.len() != 3 {
+            return false;
+        }
+        match &self.veto_threshold {
+            Some(veto_threshold) => !vetoed(total_supply, tally[1], veto_threshold),
+            None => false,
+        }
+    }
+}
+
+/// Whether `no_votes` reach `veto_threshold` of `total_supply`. The bound is inclusive and
+/// compared exactly. An invalid threshold always vetoes.
+pub fn vetoed(total_supply: U256, no_votes: U256, veto_threshold: &Ratio) -> bool {
+    !veto_threshold.is_valid() || veto_threshold.reached(no_votes, total_supply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEN_PERCENT: Ratio = Ratio {
+        numerator: 1,
+        denominator: 10,
+    };
+
+    #[test]
+    fn test_veto_threshold() {
+        // A 10% veto threshold over a supply of 1000.
+        let total_supply = U256::from(1000);
+        assert!(!vetoed(total_supply, U256::from(99), &TEN_PERCENT));
+        assert!(vetoed(total_supply, U256::from(100), &TEN_PERCENT));
+        assert!(!vetoed(total_supply, U256::ZERO, &TEN_PERCENT));
+    }
+
+    #[test]
+    fn test_veto_threshold_is_exact() {
+        // A third of a supply of 10 is not reached by 3 votes, which a rounded 333333 parts per
+        // million threshold would misjudge.
+        let third = Ratio {
+            numerator: 1,
+            denominator: 3,
+        };
+        assert!(!vetoed(U256::from(10), U256::from(3), &third));
+        assert!(vetoed(U256::from(9), U256::from(3), &third));
+        assert!(!vetoed(U256::MAX, U256::MAX / U256::from(4), &third));
+    }
+
+    #[test]
+    fn test_invalid_veto_threshold_vetoes() {
+        let invalid = Ratio {
+            numerator: 1,
+            denominator: 0,
+        };
+        assert!(vetoed(U256::from(1000), U256::ZERO, &invalid));
+    }
+}
@@ -9,6 +9,15 @@ pub trait VotingPowerStrategy {
 
 mod balance_of;
 mod get_past_votes;
+mod membership;
+mod merkle_census;
+mod min_balance_of;
 
 pub use balance_of::BalanceOf;
 pub use get_past_votes::GetPastVotes;
+pub use membership::{
+    default_member_voting_power, membership_supply, MemberCall, MemberCountCall, Membership,
+    MEMBER_VOTING_POWER,
+};
+pub use merkle_census::{census_leaf, verify_census_proof, CensusProof, MerkleCensus};
+pub use min_balance_of::{lookback_block, MinBalanceOf};
@@ -0,0 +1,158 @@
+use super::VotingPowerStrategy;
+use crate::{Asset, GuestEvmEnv};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::{sol, SolValue};
+use risc0_steel::Contract;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+sol! {
+    /// Merkle census function signatures.
+    interface IMerkleCensus {
+        function censusRoot() external view returns (bytes32);
+        function totalWeight() external view returns (uint256);
+    }
+}
+
+/// Census leaf supplied by the voter together with its inclusion proof. `asset` is the census
+/// contract the leaf belongs to, so a config can hold several censuses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CensusProof {
+    pub asset: Address,
+    pub account: Address,
+    pub weight: U256,
+    pub proof: Vec<B256>,
+}
+
+/// Hashes a census leaf the same way OpenZeppelin's `StandardMerkleTree` does for
+/// `["address", "uint256"]` leaves: `keccak256(keccak256(abi.encode(account, weight)))`.
+pub fn census_leaf(account: Address, weight: U256) -> B256 {
+    keccak256(keccak256((account, weight).abi_encode()))
+}
+
+/// Verifies a Merkle proof built with sorted pairs, as in OpenZeppelin's `MerkleProof`.
+pub fn verify_census_proof(root: B256, leaf: B256, proof: &[B256]) -> bool {
+    let computed_root = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak256([node.as_slice(), sibling.as_slice()].concat())
+        } else {
+            keccak256([sibling.as_slice(), node.as_slice()].concat())
+        }
+    });
+    computed_root == root
+}
+
+/// Voting power defined by an off-chain census committed on-chain as a Merkle root.
+#[derive(Default)]
+pub struct MerkleCensus {
+    proofs: HashMap<(Address, Address), CensusProof>,
+}
+
+impl MerkleCensus {
+    pub fn new(proofs: Vec<CensusProof>) -> Self {
+        Self {
+            proofs: proofs
+                .into_iter()
+                .map(|proof| ((proof.asset, proof.account), proof))
+                .collect(),
+        }
+    }
+}
+
+impl VotingPowerStrategy for MerkleCensus {
+    fn process(&self, env: &GuestEvmEnv, account: Address, asset: &Asset) -> U256 {
+        // Accounts without a leaf are not part of the census.
+        let Some(census_proof) = self.proofs.get(&(asset.contract, account)) else {
+            return U256::ZERO;
+        };
+
+        let census_contract = Contract::new(asset.contract, env);
+        let root_call = IMerkleCensus::censusRootCall {};
+        let root = census_contract.call_builder(&root_call).call();
+
+        assert!(
+            verify_census_proof(
+                root._0,
+                census_leaf(account, census_proof.weight),
+                &census_proof.proof
+            ),
+            "Invalid census proof for {}",
+            account
+        );
+        census_proof.weight
+    }
+
+    fn get_supply(&self, env: &GuestEvmEnv, asset: &Asset) -> U256 {
+        let census_contract = Contract::new(asset.contract, env);
+        let total_weight_call = IMerkleCensus::totalWeightCall {};
+        let total_weight = census_contract.call_builder(&total_weight_call).call();
+        total_weight._0
+    }
+}
+
+// Unit tests module
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_census_proof() {
+        let alice = census_leaf(
+            address!("8bF1e340055c7dE62F11229A149d3A1918de3d74"),
+            U256::from(10),
+        );
+        let bob = census_leaf(
+            address!("185Bb1cca668C474214e934028A3e4BB7A5E6525"),
+            U256::from(5),
+        );
+        let carol = census_leaf(
+            address!("32Bb2dB7826cf342743fe80832Fe4DF725879C2D"),
+            U256::from(1),
+        );
+
+        let hash_pair = |a: B256, b: B256| {
+            let (left, right) = if a <= b { (a, b) } else { (b, a) };
+            keccak256([left.as_slice(), right.as_slice()].concat())
+        };
+        let alice_bob = hash_pair(alice, bob);
+        let root = hash_pair(alice_bob, carol);
+
+        assert!(verify_census_proof(root, alice, &[bob, carol]));
+        assert!(verify_census_proof(root, bob, &[alice, carol]));
+        assert!(verify_census_proof(root, carol, &[alice_bob]));
+        assert!(!verify_census_proof(root, carol, &[alice]));
+
+        let forged = census_leaf(
+            address!("8bF1e340055c7dE62F11229A149d3A1918de3d74"),
+            U256::from(11),
+        );
+        assert!(!verify_census_proof(root, forged, &[bob, carol]));
+    }
+
+    #[test]
+    fn test_proofs_are_keyed_by_census() {
+        let account = address!("8bF1e340055c7dE62F11229A149d3A1918de3d74");
+        let first = address!("185Bb1cca668C474214e934028A3e4BB7A5E6525");
+        let second = address!("32Bb2dB7826cf342743fe80832Fe4DF725879C2D");
+        let census = MerkleCensus::new(vec![
+            CensusProof {
+                asset: first,
+                account,
+                weight: U256::from(10),
+                proof: Vec::new(),
+            },
+            CensusProof {
+                asset: second,
+                account,
+                weight: U256::from(3),
+                proof: Vec::new(),
+            },
+        ]);
+
+        assert_eq!(census.proofs[&(first, account)].weight, U256::from(10));
+        assert_eq!(census.proofs[&(second, account)].weight, U256::from(3));
+    }
+}
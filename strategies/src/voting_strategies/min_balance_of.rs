@@ -0,0 +1,106 @@
+use super::{BalanceOf, VotingPowerStrategy};
+use crate::{Asset, GuestEvmEnv};
+use alloy_primitives::{Address, U256};
+use risc0_steel::{ethereum::EthBlockHeader, Commitment};
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of the block `offset` blocks before `snapshot`, or `None` unless the offset lies in
+/// `1..=snapshot`.
+pub fn lookback_block(snapshot: u64, offset: u64) -> Option<u64> {
+    if offset == 0 {
+        return None;
+    }
+    snapshot.checked_sub(offset)
+}
+
+/// `balanceOf` taken as the minimum over the snapshot block and the asset's look-back blocks,
+/// so tokens borrowed right before the snapshot do not count.
+#[derive(Default)]
+pub struct MinBalanceOf {
+    lookback_envs: BTreeMap<u64, GuestEvmEnv>,
+}
+
+impl MinBalanceOf {
+    /// Ties every look-back environment to the snapshot block. `ancestors` must hold the
+    /// headers from the snapshot's parent down to the oldest look-back block.
+    pub fn new(
+        snapshot_env: &GuestEvmEnv,
+        ancestors: Vec<EthBlockHeader>,
+        lookback_envs: Vec<GuestEvmEnv>,
+    ) -> Self {
+        // Walk the parent hashes from the snapshot block down to the oldest ancestor.
+        let mut ancestor_hashes = HashMap::new();
+        let mut parent_hash = snapshot_env.header().parent_hash;
+        for header in &ancestors {
+            let hash = header.hash_slow();
+            assert_eq!(
+                hash, parent_hash,
+                "Invalid ancestor header: {}",
+                header.number
+            );
+            ancestor_hashes.insert(header.number, hash);
+            parent_hash = header.parent_hash;
+        }
+
+        let lookback_envs = lookback_envs
+            .into_iter()
+            .map(|env| {
+                let number = env.header().number;
+                assert_eq!(
+                    ancestor_hashes.get(&number),
+                    Some(&env.commitment().digest),
+                    "Look-back block {} is not an ancestor of the snapshot",
+                    number
+                );
+                (number, env)
+            })
+            .collect();
+
+        Self { lookback_envs }
+    }
+
+    /// Commitments of the look-back blocks, ordered by block number.
+    pub fn commitments(&self) -> Vec<Commitment> {
+        self.lookback_envs
+            .values()
+            .map(|env| env.commitment().clone())
+            .collect()
+    }
+}
+
+impl VotingPowerStrategy for MinBalanceOf {
+    fn process(&self, env: &GuestEvmEnv, account: Address, asset: &Asset) -> U256 {
+        let snapshot = env.header().number;
+        asset.lookback_blocks.iter().fold(
+            BalanceOf.process(env, account, asset),
+            |balance, offset| {
+                let number = lookback_block(snapshot, *offset)
+                    .unwrap_or_else(|| panic!("Invalid look-back offset: {}", offset));
+                let lookback_env = self
+                    .lookback_envs
+                    .get(&number)
+                    .unwrap_or_else(|| panic!("Look-back block not provided: {}", offset));
+                balance.min(BalanceOf.process(lookback_env, account, asset))
+            },
+        )
+    }
+
+    fn get_supply(&self, env: &GuestEvmEnv, asset: &Asset) -> U256 {
+        BalanceOf.get_supply(env, asset)
+    }
+}
+
+// Unit tests module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookback_block() {
+        assert_eq!(lookback_block(100, 1), Some(99));
+        assert_eq!(lookback_block(100, 100), Some(0));
+        assert_eq!(lookback_block(100, 0), None);
+        assert_eq!(lookback_block(100, 101), None);
+        assert_eq!(lookback_block(0, 1), None);
+    }
+}
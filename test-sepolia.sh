@@ -4,7 +4,6 @@ cargo build
 
 # These are some examples of live values that you can use to test the publisher
 export TOYKEN_ADDRESS=0x185Bb1cca668C474214e934028A3e4BB7A5E6525
-export PROVING_BLOCK_NUMBER=7087022
 export VOTER_SIGNATURE="476d1ca40c07dad98cad9acf0b08e673ae0ea7b2efb01453bd123333c800c6ee3b6f2a642e7aa03e777cfea4fec2c790ca99515a2d4acad0adbf062602ef0b9d1c"
 export VOTER=0x8bF1e340055c7dE62F11229A149d3A1918de3d74
 #export VOTER=0x8bF1e340055c7dE62F11229A149d3A1918de3d74
@@ -26,7 +25,6 @@ echo "|                                                                     |"
 echo "----------------------------------------------------------------------"
 echo ""
 echo "ERC20 Toyken Address: $TOYKEN_ADDRESS"
-echo "Initial block number: $PROVING_BLOCK_NUMBER"
 echo "Counter Address: $COUNTER_ADDRESS"
 echo "Address: $USER_ADDRESS"
 echo "Counter value: $COUNTER_VALUE"
@@ -36,7 +34,6 @@ echo "Publishing a new state..."
 cargo run --bin publisher -- \
     --chain-id=11155111 \
     --rpc-url=${RPC_URL} \
-    --block-number=${PROVING_BLOCK_NUMBER:?} \
     --voter-signature=${VOTER_SIGNATURE} \
     --voter=${VOTER} \
     --dao-address=${DAO_ADDRESS} \
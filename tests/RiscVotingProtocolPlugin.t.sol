@@ -0,0 +1,583 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+pragma solidity ^0.8.17;
+
+import {Test} from "forge-std/Test.sol";
+import {IDAO} from "@aragon/osx/core/dao/IDAO.sol";
+import {ReceiptClaim, ReceiptClaimLib} from "risc0/IRiscZeroVerifier.sol";
+import {Steel, Beacon, Encoding} from "risc0/steel/Steel.sol";
+
+import {MajorityVotingBase} from "../contracts/MajorityVotingBase.sol";
+import {RiscVotingProtocolPlugin} from "../contracts/RiscVotingProtocolPlugin.sol";
+import {IChainBlockOracle} from "../contracts/IChainBlockOracle.sol";
+import {Journal} from "../contracts/Journal.sol";
+import {RiscZeroMockVerifier} from "../contracts/mocks/RiscZeroMockVerifier.sol";
+
+/// @notice Exposes the commitment checks of the plugin for a proposal written straight to storage.
+contract RiscVotingProtocolPluginHarness is RiscVotingProtocolPlugin {
+    function setSnapshot(
+        uint256 _proposalId,
+        uint64 _snapshotBlock,
+        bytes32 _snapshotBlockHash,
+        uint64 _snapshotBeaconTimestamp,
+        bytes32 _snapshotBeaconRoot
+    ) external {
+        ProposalParameters storage parameters = proposals[_proposalId].parameters;
+        parameters.snapshotBlock = _snapshotBlock;
+        parameters.snapshotBlockHash = _snapshotBlockHash;
+        parameters.snapshotBeaconTimestamp = _snapshotBeaconTimestamp;
+        parameters.snapshotBeaconRoot = _snapshotBeaconRoot;
+    }
+
+    function validateCommitment(
+        uint256 _proposalId,
+        Steel.Commitment memory _commitment,
+        Journal.ChainCommitment[] memory _chainCommitments
+    ) external view {
+        _validateCommitment(_commitment, _chainCommitments, _proposalId);
+    }
+
+    function setChainOracleUnchecked(
+        uint64 _chainId,
+        IChainBlockOracle _oracle
+    ) external {
+        _setChainOracle(_chainId, _oracle);
+    }
+
+    function pinChainBlocks(uint256 _proposalId) external {
+        ChainBlock[] memory chainBlocks = currentChainBlocks();
+        for (uint256 i; i < chainBlocks.length; ++i) {
+            proposalChainBlocks[_proposalId].push(chainBlocks[i]);
+        }
+    }
+
+    function setVotingSettingsUnchecked(
+        VotingSettings calldata _votingSettings
+    ) external {
+        _updateVotingSettings(_votingSettings);
+    }
+
+    function createMultiOptionProposalUnchecked(
+        IDAO.Action[] calldata _actions,
+        uint8[] calldata _actionOptions,
+        uint8 _optionCount
+    ) external returns (uint256) {
+        return
+            _createMultiOptionProposal(
+                "",
+                _actions,
+                _actionOptions,
+                0,
+                0,
+                0,
+                _optionCount
+            );
+    }
+
+    function snapshotBeaconRoot() external view returns (bytes32) {
+        return _snapshotBeaconRoot();
+    }
+}
+
+/// @notice Reports a fixed block of another chain.
+contract MockChainBlockOracle is IChainBlockOracle {
+    uint64 blockNumber;
+    bytes32 blockHash;
+
+    constructor(uint64 _blockNumber, bytes32 _blockHash) {
+        blockNumber = _blockNumber;
+        blockHash = _blockHash;
+    }
+
+    function latestBlock() external view returns (uint64, bytes32) {
+        return (blockNumber, blockHash);
+    }
+}
+
+/// @notice Answers EIP-4788 queries with a root derived from the queried timestamp.
+contract MockBeaconRoots {
+    fallback(bytes calldata input) external returns (bytes memory) {
+        return abi.encode(keccak256(input));
+    }
+}
+
+contract RiscVotingProtocolPluginTest is Test {
+    using ReceiptClaimLib for ReceiptClaim;
+
+    uint256 constant PROPOSAL_ID = 0;
+    uint64 constant SNAPSHOT_BLOCK = 7087022;
+    bytes32 constant SNAPSHOT_BLOCK_HASH = bytes32(uint256(1));
+    uint64 constant SNAPSHOT_TIMESTAMP = 1727000000;
+    bytes32 constant SNAPSHOT_BEACON_ROOT = bytes32(uint256(2));
+    bytes32 constant PROPOSAL_PROTOCOL_IMAGE_ID = bytes32(uint256(3));
+    bytes4 constant VERIFIER_SELECTOR = bytes4(0xFFFFFFFF);
+
+    RiscVotingProtocolPluginHarness plugin;
+
+    function setUp() public {
+        plugin = new RiscVotingProtocolPluginHarness();
+        plugin.setSnapshot(
+            PROPOSAL_ID,
+            SNAPSHOT_BLOCK,
+            SNAPSHOT_BLOCK_HASH,
+            SNAPSHOT_TIMESTAMP,
+            SNAPSHOT_BEACON_ROOT
+        );
+    }
+
+    function commitment(
+        uint240 id,
+        uint16 version,
+        bytes32 digest
+    ) internal pure returns (Steel.Commitment memory result) {
+        result.id = Encoding.encodeVersionedID(id, version);
+        result.digest = digest;
+    }
+
+    function noChainCommitments()
+        internal
+        pure
+        returns (Journal.ChainCommitment[] memory)
+    {
+        return new Journal.ChainCommitment[](0);
+    }
+
+    function chainCommitment(
+        uint64 chainId,
+        uint240 blockNumber,
+        bytes32 blockHash
+    ) internal pure returns (Journal.ChainCommitment memory result) {
+        result.chainId = chainId;
+        result.commitment = commitment(blockNumber, 0, blockHash);
+    }
+
+    function test_BlockCommitmentOfSnapshotIsValid() public view {
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BLOCK_HASH),
+            noChainCommitments()
+        );
+    }
+
+    function test_BlockCommitmentOfOtherBlockIsRejected() public {
+        vm.expectRevert("Invalid commitment");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK + 1, 0, SNAPSHOT_BLOCK_HASH),
+            noChainCommitments()
+        );
+
+        vm.expectRevert("Invalid commitment");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BEACON_ROOT),
+            noChainCommitments()
+        );
+    }
+
+    function test_BeaconCommitmentOfSnapshotIsValid() public view {
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_TIMESTAMP, 1, SNAPSHOT_BEACON_ROOT),
+            noChainCommitments()
+        );
+    }
+
+    function test_BeaconCommitmentOfOtherRootIsRejected() public {
+        vm.expectRevert("Invalid commitment");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_TIMESTAMP + 12, 1, SNAPSHOT_BEACON_ROOT),
+            noChainCommitments()
+        );
+
+        vm.expectRevert("Invalid commitment");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_TIMESTAMP, 1, SNAPSHOT_BLOCK_HASH),
+            noChainCommitments()
+        );
+    }
+
+    function test_BeaconCommitmentWithoutStoredRootIsRejected() public {
+        plugin.setSnapshot(
+            PROPOSAL_ID,
+            SNAPSHOT_BLOCK,
+            SNAPSHOT_BLOCK_HASH,
+            SNAPSHOT_TIMESTAMP,
+            bytes32(0)
+        );
+
+        vm.expectRevert("Invalid commitment");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_TIMESTAMP, 1, bytes32(0)),
+            noChainCommitments()
+        );
+    }
+
+    function test_UnsupportedCommitmentVersionIsRejected() public {
+        vm.expectRevert("Invalid commitment version");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 2, SNAPSHOT_BLOCK_HASH),
+            noChainCommitments()
+        );
+    }
+
+    function test_ChainCommitmentsMustMatchPinnedBlocks() public {
+        plugin.setChainOracleUnchecked(
+            10,
+            new MockChainBlockOracle(120000000, bytes32(uint256(10)))
+        );
+        plugin.setChainOracleUnchecked(
+            8453,
+            new MockChainBlockOracle(20000000, bytes32(uint256(8453)))
+        );
+        plugin.pinChainBlocks(PROPOSAL_ID);
+
+        Journal.ChainCommitment[]
+            memory chainCommitments = new Journal.ChainCommitment[](2);
+        chainCommitments[0] = chainCommitment(
+            10,
+            120000000,
+            bytes32(uint256(10))
+        );
+        chainCommitments[1] = chainCommitment(
+            8453,
+            20000000,
+            bytes32(uint256(8453))
+        );
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BLOCK_HASH),
+            chainCommitments
+        );
+
+        // A journal leaving out a pinned chain is rejected.
+        vm.expectRevert("Invalid chain commitments");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BLOCK_HASH),
+            noChainCommitments()
+        );
+
+        // So is a journal committing to another block of a pinned chain.
+        chainCommitments[1] = chainCommitment(
+            8453,
+            20000001,
+            bytes32(uint256(8453))
+        );
+        vm.expectRevert("Invalid chain commitments");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BLOCK_HASH),
+            chainCommitments
+        );
+    }
+
+    function test_UnpinnedChainCommitmentsAreRejected() public {
+        Journal.ChainCommitment[]
+            memory chainCommitments = new Journal.ChainCommitment[](1);
+        chainCommitments[0] = chainCommitment(
+            10,
+            120000000,
+            bytes32(uint256(10))
+        );
+
+        vm.expectRevert("Invalid chain commitments");
+        plugin.validateCommitment(
+            PROPOSAL_ID,
+            commitment(SNAPSHOT_BLOCK, 0, SNAPSHOT_BLOCK_HASH),
+            chainCommitments
+        );
+    }
+
+    function test_ChainOraclesAreKeptInChainIdOrder() public {
+        plugin.setChainOracleUnchecked(
+            8453,
+            new MockChainBlockOracle(20000000, bytes32(uint256(8453)))
+        );
+        plugin.setChainOracleUnchecked(
+            10,
+            new MockChainBlockOracle(120000000, bytes32(uint256(10)))
+        );
+        plugin.setChainOracleUnchecked(
+            42161,
+            new MockChainBlockOracle(250000000, bytes32(uint256(42161)))
+        );
+        plugin.setChainOracleUnchecked(8453, IChainBlockOracle(address(0)));
+
+        RiscVotingProtocolPlugin.ChainBlock[] memory chainBlocks = plugin
+            .currentChainBlocks();
+        assertEq(chainBlocks.length, 2);
+        assertEq(chainBlocks[0].chainId, 10);
+        assertEq(chainBlocks[0].blockNumber, 120000000);
+        assertEq(chainBlocks[1].chainId, 42161);
+        assertEq(chainBlocks[1].blockHash, bytes32(uint256(42161)));
+    }
+
+    function proposalActions()
+        internal
+        pure
+        returns (IDAO.Action[] memory actions)
+    {
+        actions = new IDAO.Action[](1);
+        actions[0] = IDAO.Action(address(1), 5, hex"abcd");
+    }
+
+    /// @dev Lets this contract create proposals with proofs checked by a mock verifier.
+    function setUpProposalProofs() internal {
+        vm.roll(100);
+        plugin.setVotingSettingsUnchecked(
+            MajorityVotingBase.VotingSettings({
+                votingMode: MajorityVotingBase.VotingMode.Standard,
+                supportThreshold: 500000,
+                minParticipation: 100000,
+                minDuration: 1 hours,
+                minProposerVotingPower: 1,
+                votingProtocolConfig: "{}",
+                verifier: address(new RiscZeroMockVerifier(VERIFIER_SELECTOR)),
+                votingProtocolImageId: bytes32(0),
+                executionProtocolImageId: bytes32(0),
+                proposalProtocolImageId: PROPOSAL_PROTOCOL_IMAGE_ID
+            })
+        );
+    }
+
+    /// @dev A journal of the current block, whose hash `blockhash` reports as zero.
+    function proposalJournal(
+        address proposer,
+        bytes32 paramsHash
+    ) internal view returns (bytes memory) {
+        Journal.ProposalJournal memory journal;
+        journal.commitment = commitment(uint240(block.number), 0, bytes32(0));
+        journal.configContract = address(plugin);
+        journal.configHash = plugin.votingProtocolConfigHash();
+        journal.proposer = proposer;
+        journal.paramsHash = paramsHash;
+        journal.votingPower = 1;
+        return abi.encode(journal);
+    }
+
+    function mockSeal(
+        bytes32 imageId,
+        bytes memory journalData
+    ) internal pure returns (bytes memory) {
+        return
+            abi.encodePacked(
+                VERIFIER_SELECTOR,
+                ReceiptClaimLib.ok(imageId, sha256(journalData)).digest()
+            );
+    }
+
+    function test_ProposalParamsHashIsPinned() public view {
+        // Pinned to the value `proposal_params_hash` returns in the `apps` crate.
+        assertEq(
+            plugin.proposalParamsHash(hex"1234", proposalActions(), 1, 0, 0),
+            0x64417dfd13b8018c00fcd8d96053a9d25b025a7b1aa6b2a4d44c1c6fb610cde9
+        );
+    }
+
+    function test_ConfigHashIsDerivedFromConfig() public {
+        setUpProposalProofs();
+
+        assertEq(plugin.votingProtocolConfigHash(), keccak256("{}"));
+    }
+
+    function test_ProposalProofCreatesOneProposal() public {
+        setUpProposalProofs();
+        bytes memory journalData = proposalJournal(
+            address(this),
+            plugin.proposalParamsHash(hex"1234", proposalActions(), 1, 0, 0)
+        );
+        bytes memory seal = mockSeal(
+            PROPOSAL_PROTOCOL_IMAGE_ID,
+            journalData
+        );
+
+        uint256 proposalId = plugin.createProposalWithProof(
+            journalData,
+            seal,
+            hex"1234",
+            proposalActions(),
+            1,
+            0,
+            0
+        );
+        assertEq(
+            plugin.votingProtocolConfigHash(proposalId),
+            keccak256("{}")
+        );
+
+        vm.expectRevert("Proof already used");
+        plugin.createProposalWithProof(
+            journalData,
+            seal,
+            hex"1234",
+            proposalActions(),
+            1,
+            0,
+            0
+        );
+    }
+
+    function test_ProposalProofIsBoundToItsParameters() public {
+        setUpProposalProofs();
+        bytes memory journalData = proposalJournal(
+            address(this),
+            plugin.proposalParamsHash(hex"1234", proposalActions(), 1, 0, 0)
+        );
+
+        vm.expectRevert("Invalid proposal parameters");
+        plugin.createProposalWithProof(
+            journalData,
+            mockSeal(PROPOSAL_PROTOCOL_IMAGE_ID, journalData),
+            hex"5678",
+            proposalActions(),
+            1,
+            0,
+            0
+        );
+    }
+
+    function test_ProposalProofIsBoundToItsProposer() public {
+        setUpProposalProofs();
+        bytes memory journalData = proposalJournal(
+            address(this),
+            plugin.proposalParamsHash(hex"1234", proposalActions(), 1, 0, 0)
+        );
+
+        vm.prank(address(0xBEEF));
+        vm.expectRevert("Invalid proposer");
+        plugin.createProposalWithProof(
+            journalData,
+            mockSeal(PROPOSAL_PROTOCOL_IMAGE_ID, journalData),
+            hex"1234",
+            proposalActions(),
+            1,
+            0,
+            0
+        );
+    }
+
+    /// @dev Two actions for option 1 and one for option 2 of a three option proposal.
+    function createMultiOptionProposal() internal returns (uint256) {
+        IDAO.Action[] memory actions = new IDAO.Action[](3);
+        actions[0] = IDAO.Action(address(1), 1, hex"01");
+        actions[1] = IDAO.Action(address(2), 2, hex"02");
+        actions[2] = IDAO.Action(address(1), 3, hex"03");
+        uint8[] memory actionOptions = new uint8[](3);
+        actionOptions[0] = 1;
+        actionOptions[1] = 2;
+        actionOptions[2] = 1;
+        return
+            plugin.createMultiOptionProposalUnchecked(
+                actions,
+                actionOptions,
+                3
+            );
+    }
+
+    /// @dev A ballot choosing option 1, proven at the proposal's snapshot block.
+    function ballotJournal(
+        uint256 proposalId,
+        address voter
+    ) internal view returns (bytes memory) {
+        uint8[] memory choices = new uint8[](1);
+        choices[0] = 1;
+        Journal.VotingJournal memory journal;
+        journal.commitment = commitment(
+            uint240(block.number - 1),
+            0,
+            blockhash(block.number - 1)
+        );
+        journal.configContract = address(plugin);
+        journal.configHash = plugin.votingProtocolConfigHash(proposalId);
+        journal.proposalId = proposalId;
+        journal.voter = voter;
+        journal.balance = 1;
+        journal.choices = choices;
+        return abi.encode(journal);
+    }
+
+    function test_MultiOptionProposalStoresActionsPerOption() public {
+        setUpProposalProofs();
+        uint256 proposalId = createMultiOptionProposal();
+
+        IDAO.Action[] memory option1 = plugin.getOptionActions(proposalId, 1);
+        assertEq(option1.length, 2);
+        assertEq(option1[0].value, 1);
+        assertEq(option1[1].value, 3);
+        IDAO.Action[] memory option2 = plugin.getOptionActions(proposalId, 2);
+        assertEq(option2.length, 1);
+        assertEq(option2[0].to, address(2));
+        assertEq(plugin.getOptionActions(proposalId, 3).length, 0);
+
+        // Nothing runs before an option wins.
+        (, , , , IDAO.Action[] memory actions, ) = plugin.getProposal(
+            proposalId
+        );
+        assertEq(actions.length, 0);
+    }
+
+    function test_MultiOptionProposalRejectsActionsOfOtherOptions() public {
+        setUpProposalProofs();
+        IDAO.Action[] memory actions = proposalActions();
+        uint8[] memory actionOptions = new uint8[](1);
+
+        actionOptions[0] = 0;
+        vm.expectRevert("Invalid action options");
+        plugin.createMultiOptionProposalUnchecked(actions, actionOptions, 2);
+
+        actionOptions[0] = 3;
+        vm.expectRevert("Invalid action options");
+        plugin.createMultiOptionProposalUnchecked(actions, actionOptions, 2);
+
+        vm.expectRevert("Invalid action options");
+        plugin.createMultiOptionProposalUnchecked(actions, new uint8[](0), 2);
+    }
+
+    function test_BallotsAreCapped() public {
+        setUpProposalProofs();
+        uint256 proposalId = createMultiOptionProposal();
+
+        uint256 maxBallots = plugin.MAX_BALLOTS();
+        for (uint256 i = 1; i <= maxBallots; ++i) {
+            bytes memory journalData = ballotJournal(
+                proposalId,
+                address(uint160(i))
+            );
+            plugin.vote(journalData, mockSeal(bytes32(0), journalData));
+        }
+        assertEq(plugin.getBallots(proposalId).length, 2 * maxBallots);
+
+        bytes memory extraBallot = ballotJournal(
+            proposalId,
+            address(uint160(maxBallots + 1))
+        );
+        bytes memory extraSeal = mockSeal(bytes32(0), extraBallot);
+        vm.expectRevert("Too many ballots");
+        plugin.vote(extraBallot, extraSeal);
+
+        // Voters who already cast a ballot can still replace it.
+        bytes memory replacement = ballotJournal(proposalId, address(1));
+        plugin.vote(replacement, mockSeal(bytes32(0), replacement));
+    }
+
+    function test_SnapshotBeaconRootIsReadFromEip4788() public {
+        vm.etch(Beacon.BEACON_ROOTS_ADDRESS, address(new MockBeaconRoots()).code);
+        vm.warp(SNAPSHOT_TIMESTAMP);
+
+        assertEq(
+            plugin.snapshotBeaconRoot(),
+            keccak256(abi.encode(uint256(SNAPSHOT_TIMESTAMP)))
+        );
+    }
+
+    function test_SnapshotBeaconRootIsZeroWithoutEip4788() public {
+        vm.etch(Beacon.BEACON_ROOTS_ADDRESS, "");
+
+        assertEq(plugin.snapshotBeaconRoot(), bytes32(0));
+    }
+}
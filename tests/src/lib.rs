@@ -11,6 +11,8 @@ fn hash_vote(
     dao_address: Address,
     proposal_id: U256,
     direction: u8,
+    amounts: &[U256],
+    choices: &[u8],
     balance: U256,
 ) -> FixedBytes<32> {
     let concat_data = [
@@ -18,6 +20,11 @@ fn hash_vote(
         dao_address.to_vec(),
         proposal_id.to_be_bytes_vec(),
         [direction].to_vec(),
+        amounts
+            .iter()
+            .flat_map(|amount| amount.to_be_bytes_vec())
+            .collect(),
+        choices.to_vec(),
         balance.to_be_bytes_vec(),
     ]
     .concat();
@@ -26,15 +33,28 @@ fn hash_vote(
     keccak256([PREFIX.as_bytes(), &hashed_data].concat())
 }
 
+/// Signs a vote. Split votes use direction 0 and pass the voting power given to abstain, yes and
+/// no in `amounts`. Ballots of multi-option proposals use direction 0 and pass the chosen options
+/// in `choices`. Both are empty for other votes.
 pub async fn get_user_vote_signature(
     chain_id: u64,
     signer: PrivateKeySigner,
     dao_address: Address,
     proposal_id: U256,
     direction: u8,
+    amounts: &[U256],
+    choices: &[u8],
     balance: U256,
 ) -> Result<Signature> {
-    let vote_hash = hash_vote(chain_id, dao_address, proposal_id, direction, balance);
+    let vote_hash = hash_vote(
+        chain_id,
+        dao_address,
+        proposal_id,
+        direction,
+        amounts,
+        choices,
+        balance,
+    );
 
     let signature = signer
         .with_chain_id(Some(chain_id))
@@ -270,9 +270,18 @@ async fn test_config_is_setup() -> Result<()> {
     let balance = U256::from_str("0").expect("Failed to parse balance");
 
     let signed_vote = hex::encode(
-        get_user_vote_signature(11155111, signer.clone(), dao, U256::from(2), 0, balance)
-            .await?
-            .as_bytes(),
+        get_user_vote_signature(
+            11155111,
+            signer.clone(),
+            dao,
+            U256::from(2),
+            0,
+            &[],
+            &[],
+            balance,
+        )
+        .await?
+        .as_bytes(),
     );
 
     let voter = EthereumWallet::from(signer).default_signer().address();
@@ -300,10 +309,6 @@ async fn test_config_is_setup() -> Result<()> {
             std::env::var("CHAIN_ID").unwrap_or_else(|_| "11155111".to_string())
         ))
         .arg(format!("--rpc-url={}", anvil.endpoint()))
-        .arg(format!(
-            "--block-number={}",
-            provider.get_block_number().await?
-        ))
         .arg(format!("--voter-signature={:?}", signed_vote))
         .arg(format!("--voter={}", voter))
         .arg(format!("--dao-address={}", dao))